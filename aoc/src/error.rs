@@ -0,0 +1,147 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error found while parsing or solving the puzzle input of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub day: u8,
+    /// Line of the input, starting at 1
+    pub line: Option<usize>,
+    /// Column of the line, starting at 1
+    pub column: Option<usize>,
+    /// Contents of the offending line
+    pub text: Option<String>,
+    pub message: String,
+}
+
+impl Error {
+    /// Error not tied to a position of the input
+    pub fn new(day: u8, message: impl Into<String>) -> Error {
+        Error {
+            day,
+            line: None,
+            column: None,
+            text: None,
+            message: message.into(),
+        }
+    }
+
+    /// Error at `column` of the input line number `line`
+    pub fn at(
+        day: u8,
+        line: usize,
+        text: &str,
+        column: usize,
+        message: impl Into<String>,
+    ) -> Error {
+        Error {
+            day,
+            line: Some(line),
+            column: Some(column),
+            text: Some(text.to_owned()),
+            message: message.into(),
+        }
+    }
+
+    /// Error pointing at `part`, which must be a slice of the input line `text`
+    pub fn at_part(
+        day: u8,
+        line: usize,
+        text: &str,
+        part: &str,
+        message: impl Into<String>,
+    ) -> Error {
+        Error::at(day, line, text, column_of(text, part), message)
+    }
+
+    /// Multi-line description of the error, quoting the offending line
+    pub fn diagnostic(&self) -> String {
+        let mut diagnostic = format!("error: {}", self);
+
+        if let (Some(line), Some(text)) = (self.line, &self.text) {
+            let gutter = " ".repeat(line.to_string().len());
+            diagnostic += &format!("\n{} |\n{} | {}", gutter, line, text);
+
+            if let Some(column) = self.column {
+                diagnostic += &format!("\n{} | {}^", gutter, " ".repeat(column - 1));
+            }
+        }
+
+        diagnostic
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {}", self.day)?;
+        if let Some(line) = self.line {
+            write!(f, ", line {}", line)?;
+        }
+        if let Some(column) = self.column {
+            write!(f, ", column {}", column)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Parses `part` of the input line `text`, pointing the error at it when it is not valid
+pub fn parse_part<T>(day: u8, line: usize, text: &str, part: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    part.parse::<T>().map_err(|err| {
+        Error::at_part(
+            day,
+            line,
+            text,
+            part,
+            format!("invalid value `{}`: {}", part, err),
+        )
+    })
+}
+
+/// Column where `part` starts inside `text`, or 1 when it is not a slice of it
+fn column_of(text: &str, part: &str) -> usize {
+    let start = text.as_ptr() as usize;
+    let offset = (part.as_ptr() as usize).wrapping_sub(start);
+
+    if offset <= text.len() {
+        text[..offset].chars().count() + 1
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_display_error_position() {
+        let error = Error::at(5, 7, "move x from 1 to 2", 6, "invalid value");
+
+        assert_eq!(error.to_string(), "day 5, line 7, column 6: invalid value");
+        assert_eq!(Error::new(1, "no elves").to_string(), "day 1: no elves");
+    }
+
+    #[test]
+    fn it_should_point_at_the_offending_part() {
+        let text = "addx 1z";
+        let parts: Vec<&str> = text.split(' ').collect();
+
+        let error = parse_part::<i64>(10, 3, text, parts[1]).unwrap_err();
+
+        assert_eq!(error.column, Some(6));
+        assert_eq!(
+            error.diagnostic(),
+            "error: day 10, line 3, column 6: invalid value `1z`: invalid digit found in string
+  |
+3 | addx 1z
+  |      ^"
+        );
+    }
+}
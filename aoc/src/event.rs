@@ -0,0 +1,61 @@
+use crate::solution::Solution;
+use std::path::PathBuf;
+
+/// Random puzzle input of a day given its size and a seed, `None` for the days it cannot generate
+pub type Generator = fn(day: u8, size: usize, seed: u64) -> Option<String>;
+
+/// Advent of code event of a year, with its solutions and puzzle data.
+///
+/// The crate of the solutions keeps the puzzle data in its `data` directory.
+pub struct Event {
+    pub year: u16,
+    /// Directory of the crate with the solutions
+    pub crate_dir: PathBuf,
+    /// Directory with the `dayN_input.txt` files and the recorded answers
+    pub data_dir: PathBuf,
+    solutions: fn() -> Vec<Box<dyn Solution>>,
+    generator: Option<Generator>,
+}
+
+impl Event {
+    pub fn new(
+        year: u16,
+        crate_dir: impl Into<PathBuf>,
+        solutions: fn() -> Vec<Box<dyn Solution>>,
+    ) -> Event {
+        let crate_dir = crate_dir.into();
+
+        Event {
+            year,
+            data_dir: crate_dir.join("data"),
+            crate_dir,
+            solutions,
+            generator: None,
+        }
+    }
+
+    /// Event able to generate random puzzle inputs with `generator`
+    pub fn with_generator(self, generator: Generator) -> Event {
+        Event {
+            generator: Some(generator),
+            ..self
+        }
+    }
+
+    /// All the available solutions, ordered by day
+    pub fn solutions(&self) -> Vec<Box<dyn Solution>> {
+        (self.solutions)()
+    }
+
+    /// Solution for the given day, if there is one
+    pub fn get_solution(&self, day: u8) -> Option<Box<dyn Solution>> {
+        self.solutions().into_iter().find(|s| s.day() == day)
+    }
+
+    /// Random puzzle input of a day, the same for the same size and seed. `None` when the
+    /// event cannot generate inputs for the day.
+    pub fn generate(&self, day: u8, size: usize, seed: u64) -> Option<String> {
+        self.generator
+            .and_then(|generate| generate(day, size, seed))
+    }
+}
@@ -0,0 +1,369 @@
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Sub};
+
+/// Position on a grid, with `x` growing to the right and `y` growing downwards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    /// Point one step away in the given direction
+    pub fn step(self, direction: Direction) -> Point {
+        self + direction.delta()
+    }
+
+    /// The four points sharing a side with this one
+    pub fn neighbours(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+
+    /// The eight points sharing a side or a corner with this one
+    pub fn neighbours_diagonal(self) -> impl Iterator<Item = Point> {
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Point::new(x, y)))
+            .filter(|delta| *delta != Point::ORIGIN)
+            .map(move |delta| self + delta)
+    }
+
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Change of position of a step in this direction
+    pub fn delta(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+        }
+    }
+}
+
+/// Rectangular grid with a value in every cell, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Grid {
+            width: 0,
+            height: 0,
+            cells: vec![],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Grid made of the given rows, or `None` when they are not all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |r| r.len());
+        if rows.iter().any(|r| r.len() != width) {
+            return None;
+        }
+
+        Some(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses a map with a cell per character and a row per line. `cell` converts a character,
+    /// returning the message of the error to report when it is not valid.
+    pub fn parse(
+        day: u8,
+        data: &str,
+        mut cell: impl FnMut(char) -> std::result::Result<T, String>,
+    ) -> Result<Grid<T>> {
+        let mut rows: Vec<Vec<T>> = vec![];
+
+        for (i, line) in data.lines().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(j, c)| {
+                    cell(c).map_err(|message| Error::at(day, i + 1, line, j + 1, message))
+                })
+                .collect::<Result<Vec<T>>>()?;
+
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(Error::at(
+                        day,
+                        i + 1,
+                        line,
+                        1,
+                        format!(
+                            "expected {} cells in the row, found {}",
+                            first.len(),
+                            row.len()
+                        ),
+                    ));
+                }
+            }
+            rows.push(row);
+        }
+
+        match Grid::from_rows(rows) {
+            Some(grid) if !grid.cells.is_empty() => Ok(grid),
+            _ => Err(Error::new(day, "the map is empty")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.index(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index(point).map(|i| &mut self.cells[i])
+    }
+
+    /// Every point of the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x as i64, y as i64)))
+    }
+
+    /// Every cell of the grid along with its point, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.cells[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// Cells met going from `from`, excluded, in the given direction until the edge of the grid
+    pub fn ray(&self, from: Point, direction: Direction) -> impl Iterator<Item = (Point, &T)> {
+        let mut point = from;
+
+        std::iter::from_fn(move || {
+            point = point.step(direction);
+            Some((point, self.get(point)?))
+        })
+    }
+
+    /// Grid of the same size with every cell converted by `f`
+    pub fn map<U>(&self, mut f: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(p, v)| f(p, v)).collect(),
+        }
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let (x, y) = (
+            usize::try_from(point.x).ok()?,
+            usize::try_from(point.y).ok()?,
+        );
+
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+}
+
+/// Unbounded grid holding values only in some of its cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    /// Sets the value of a cell, returning the one it had
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// Number of cells with a value
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(p, v)| (*p, v))
+    }
+
+    /// Top left and bottom right corners of the smallest rectangle holding every value
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_grid() -> Grid<u32> {
+        Grid::parse(8, "303\n255\n653", |c| {
+            c.to_digit(10).ok_or_else(|| "not a digit".to_owned())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn it_should_parse_character_maps() {
+        let grid = get_grid();
+        let error =
+            Grid::parse(8, "303\n2x5", |c| c.to_digit(10).ok_or(String::new())).unwrap_err();
+        let uneven =
+            Grid::parse(8, "303\n25", |c| c.to_digit(10).ok_or(String::new())).unwrap_err();
+
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&5));
+        assert_eq!(grid.get(Point::new(3, 1)), None);
+        assert_eq!((error.line, error.column), (Some(2), Some(2)));
+        assert_eq!(uneven.line, Some(2));
+    }
+
+    #[test]
+    fn it_should_iterate_rows_columns_and_rays() {
+        let grid = get_grid();
+
+        let row: Vec<u32> = grid.row(1).copied().collect();
+        let column: Vec<u32> = grid.column(2).copied().collect();
+        let ray: Vec<u32> = grid
+            .ray(Point::new(2, 2), Direction::Up)
+            .map(|(_, v)| *v)
+            .collect();
+
+        assert_eq!(row, [2, 5, 5]);
+        assert_eq!(column, [3, 5, 3]);
+        assert_eq!(ray, [5, 3]);
+    }
+
+    #[test]
+    fn it_should_find_neighbours_and_bounds() {
+        let mut sparse: SparseGrid<()> = SparseGrid::new();
+        for point in Point::ORIGIN.neighbours() {
+            sparse.insert(point, ());
+        }
+
+        assert_eq!(Point::ORIGIN.neighbours_diagonal().count(), 8);
+        assert_eq!(sparse.len(), 4);
+        assert_eq!(
+            sparse.bounds(),
+            Some((Point::new(-1, -1), Point::new(1, 1)))
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod error;
+pub mod event;
+pub mod grid;
+pub mod parse;
+pub mod solution;
+
+pub use error::{Error, Result};
+pub use event::{Event, Generator};
+pub use solution::{Answer, Solution, PARTS};
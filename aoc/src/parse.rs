@@ -0,0 +1,284 @@
+use crate::error::{parse_part, Error, Result};
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+/// Line of the puzzle input of a day, along with its number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub day: u8,
+    /// Number of the line, starting at 1
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Error at `column` of the line
+    pub fn error(&self, column: usize, message: impl Into<String>) -> Error {
+        Error::at(self.day, self.number, self.text, column, message)
+    }
+
+    /// Error pointing at `part`, which must be a slice of the line
+    pub fn error_at(&self, part: &str, message: impl Into<String>) -> Error {
+        Error::at_part(self.day, self.number, self.text, part, message)
+    }
+
+    /// Parses `part` of the line, pointing the error at it when it is not valid
+    pub fn parse<T>(&self, part: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        parse_part(self.day, self.number, self.text, part)
+    }
+
+    /// Rest of a line starting with `prefix`, without surrounding whitespace
+    pub fn field(&self, prefix: &str) -> Result<&'a str> {
+        self.text
+            .strip_prefix(prefix)
+            .map(str::trim)
+            .ok_or_else(|| self.error(1, format!("expected `{}`", prefix.trim())))
+    }
+
+    /// Splits `part` of the line into exactly `N` pieces, reporting `expected` otherwise
+    pub fn split<const N: usize>(
+        &self,
+        part: &'a str,
+        separator: char,
+        expected: &str,
+    ) -> Result<[&'a str; N]> {
+        let pieces: Vec<&'a str> = part.split(separator).collect();
+
+        pieces.try_into().map_err(|_| self.error_at(part, expected))
+    }
+
+    /// Every integer written in the line, see [`integers`]
+    pub fn integers<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        integers(self.text)
+            .into_iter()
+            .map(|part| self.parse(part))
+            .collect()
+    }
+
+    /// Matches the line against a template where every `{}` stands for an integer, like
+    /// `move {} from {} to {}`, and parses those integers
+    pub fn scan<T, const N: usize>(&self, template: &str) -> Result<[T; N]>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let expected = || self.error(1, format!("expected `{}`", template.replace("{}", "N")));
+        let mut literals = template.split("{}");
+        let mut rest = self
+            .text
+            .trim_end()
+            .strip_prefix(literals.next().unwrap_or_default())
+            .ok_or_else(expected)?;
+        let mut parts: Vec<&str> = vec![];
+
+        for literal in literals {
+            let length = integer_length(rest);
+            if length == 0 {
+                return Err(expected());
+            }
+
+            parts.push(&rest[..length]);
+            rest = rest[length..].strip_prefix(literal).ok_or_else(expected)?;
+        }
+        if !rest.is_empty() {
+            return Err(expected());
+        }
+
+        let values = parts
+            .into_iter()
+            .map(|part| self.parse(part))
+            .collect::<Result<Vec<T>>>()?;
+
+        Ok(values
+            .try_into()
+            .unwrap_or_else(|_| panic!("`{}` does not have {} integers", template, N)))
+    }
+
+    /// Cells of a drawing made of columns `width` characters wide separated by `gap` characters,
+    /// like the `[A] [B]` crates of day 5. The last cell is shorter when the line ends early.
+    pub fn columns(&self, width: usize, gap: usize) -> Vec<&'a str> {
+        let bounds: Vec<usize> = self
+            .text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([self.text.len()])
+            .collect();
+        let length = bounds.len() - 1;
+
+        (0..length)
+            .step_by(width + gap)
+            .map(|start| &self.text[bounds[start]..bounds[(start + width).min(length)]])
+            .collect()
+    }
+}
+
+/// Numbered lines of the input
+pub fn lines(day: u8, data: &str) -> impl Iterator<Item = Line<'_>> {
+    data.lines().enumerate().map(move |(i, text)| Line {
+        day,
+        number: i + 1,
+        text,
+    })
+}
+
+/// Lines read one at a time from `reader`, each converted by `f`, so that the input is never
+/// held in memory as a whole. The same buffer is used for every line.
+pub fn read_lines<T>(
+    day: u8,
+    mut reader: impl BufRead,
+    mut f: impl FnMut(Line) -> Result<T>,
+) -> impl Iterator<Item = Result<T>> {
+    let mut text = String::new();
+    let mut number = 0;
+
+    std::iter::from_fn(move || {
+        text.clear();
+        number += 1;
+
+        match reader.read_line(&mut text) {
+            Ok(0) => None,
+            Ok(_) => {
+                let text = text.strip_suffix('\n').unwrap_or(&text);
+
+                Some(f(Line {
+                    day,
+                    number,
+                    text: text.strip_suffix('\r').unwrap_or(text),
+                }))
+            }
+            Err(err) => Some(Err(read_error(day, number, err))),
+        }
+    })
+}
+
+/// Error reading the line number `line` of the input
+pub fn read_error(day: u8, line: usize, err: io::Error) -> Error {
+    Error {
+        line: Some(line),
+        ..Error::new(day, format!("could not read the input: {}", err))
+    }
+}
+
+/// Groups of consecutive lines separated by blank lines, skipping the blank lines themselves
+pub fn blocks(day: u8, data: &str) -> Vec<Vec<Line<'_>>> {
+    let mut blocks: Vec<Vec<Line>> = vec![];
+    let mut block: Vec<Line> = vec![];
+
+    for line in lines(day, data) {
+        if !line.text.trim().is_empty() {
+            block.push(line);
+        } else if !block.is_empty() {
+            blocks.push(std::mem::take(&mut block));
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// Every integer written in `text`, as slices of it. A `-` is only taken as a sign when it does
+/// not follow a digit, so that ranges like `2-4` give `2` and `4`.
+pub fn integers(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut integers: Vec<&str> = vec![];
+    let mut start = 0;
+
+    while start < text.len() {
+        let after_digit = start > 0 && bytes[start - 1].is_ascii_digit();
+        let length = if bytes[start] == b'-' && after_digit {
+            0
+        } else {
+            integer_length(&text[start..])
+        };
+
+        if length > 0 {
+            integers.push(&text[start..start + length]);
+            start += length;
+        } else {
+            start += text[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    integers
+}
+
+/// Length of the integer `text` starts with, or 0 when it does not start with one
+fn integer_length(text: &str) -> usize {
+    let sign = usize::from(text.starts_with('-'));
+    let digits = text[sign..]
+        .bytes()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+
+    if digits > 0 {
+        sign + digits
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_split_blocks() {
+        let blocks = blocks(1, "1000\n2000\n\n\n3000\n  \n4000\n");
+
+        let numbers: Vec<Vec<usize>> = blocks
+            .iter()
+            .map(|b| b.iter().map(|l| l.number).collect())
+            .collect();
+
+        assert_eq!(numbers, [vec![1, 2], vec![5], vec![7]]);
+    }
+
+    #[test]
+    fn it_should_extract_integers() {
+        let line = Line {
+            day: 11,
+            number: 2,
+            text: "  Starting items: 79, -98, 2-4",
+        };
+
+        assert_eq!(line.integers::<i64>().unwrap(), [79, -98, 2, 4]);
+        assert_eq!(integers("x=-3,y=12abc"), ["-3", "12"]);
+        assert_eq!(integers("no numbers - here"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn it_should_scan_templates_and_columns() {
+        let line = |text| Line {
+            day: 5,
+            number: 4,
+            text,
+        };
+
+        let moved: [i32; 3] = line("move 3 from 1 to 2")
+            .scan("move {} from {} to {}")
+            .unwrap();
+        let invalid = line("move x from 1 to 2")
+            .scan::<i32, 3>("move {} from {} to {}")
+            .unwrap_err();
+        let too_big = line("move 99999999999 from 1 to 2")
+            .scan::<i32, 3>("move {} from {} to {}")
+            .unwrap_err();
+
+        assert_eq!(moved, [3, 1, 2]);
+        assert_eq!(invalid.message, "expected `move N from N to N`");
+        assert_eq!((too_big.line, too_big.column), (Some(4), Some(6)));
+        assert_eq!(line("[Z] [M]   ").columns(3, 1), ["[Z]", "[M]", "  "]);
+        assert_eq!(line("    [D]").columns(3, 1), ["   ", "[D]"]);
+    }
+}
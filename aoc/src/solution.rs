@@ -0,0 +1,134 @@
+use crate::error::{Error, Result};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Parts every day of the advent calendar has
+pub const PARTS: [u8; 2] = [1, 2];
+
+/// Answer produced by one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Number(value.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Number(value.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Number(value.into())
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        Answer::Number(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// Solution to the puzzle of a single day.
+///
+/// The input is parsed once with `parse` and kept by the implementor, so both
+/// parts can be solved from the same parsed data.
+pub trait Solution {
+    /// Day of the advent calendar
+    fn day(&self) -> u8;
+
+    /// Parses the puzzle input
+    fn parse(&mut self, data: &str) -> Result<()>;
+
+    /// Solves the first part from the parsed input
+    fn part1(&self) -> Result<Answer>;
+
+    /// Solves the second part from the parsed input
+    fn part2(&self) -> Result<Answer>;
+
+    /// Solves the given part, failing when the puzzle does not have it
+    fn solve(&self, part: u8) -> Result<Answer> {
+        match part {
+            1 => self.part1(),
+            2 => self.part2(),
+            _ => Err(Error::new(self.day(), format!("there is no part {}", part))),
+        }
+    }
+
+    /// Parameters of the puzzle that can be changed with `set_param`, like the number of rounds
+    /// of a simulation, along with their current values
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+
+    /// Changes the value of one of the `params`
+    fn set_param(&mut self, name: &str, _value: &str) -> Result<()> {
+        Err(unknown_param(self.day(), name))
+    }
+}
+
+/// Error for a parameter the solution of `day` does not have
+pub fn unknown_param(day: u8, name: &str) -> Error {
+    Error::new(day, format!("there is no parameter `{}`", name))
+}
+
+/// Parses the value given to the parameter `name`
+pub fn parse_param<T>(day: u8, name: &str, value: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value.parse().map_err(|err| {
+        Error::new(
+            day,
+            format!("invalid value `{}` for `{}`: {}", value, name, err),
+        )
+    })
+}
+
+/// Parses the value given to the parameter `name`, rejecting the ones outside of `range`
+pub fn parse_param_in<T>(day: u8, name: &str, value: &str, range: RangeInclusive<T>) -> Result<T>
+where
+    T: FromStr + PartialOrd + fmt::Display,
+    T::Err: fmt::Display,
+{
+    let parsed = parse_param(day, name, value)?;
+
+    if range.contains(&parsed) {
+        Ok(parsed)
+    } else {
+        Err(Error::new(
+            day,
+            format!(
+                "invalid value `{}` for `{}`: expected {} to {}",
+                value,
+                name,
+                range.start(),
+                range.end()
+            ),
+        ))
+    }
+}
@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ed_2022::day1::{self, Arithmetic, Inventory};
+
+/// Top three elves of a large generated inventory, collecting every elf and sorting them against
+/// streaming them through a bounded heap
+fn top_three(c: &mut Criterion) {
+    let data = ed_2022::generate(1, 100_000, 0).unwrap();
+    let mut group = c.benchmark_group("day1 top three");
+
+    group.bench_function("sorted inventory", |b| {
+        b.iter(|| day1::top_three_calories(black_box(&data)))
+    });
+    group.bench_function("sorted inventory from reader", |b| {
+        b.iter(|| {
+            Inventory::from_reader(black_box(data.as_bytes()), Arithmetic::Checked)?.top_total(3)
+        })
+    });
+    group.bench_function("streaming heap from reader", |b| {
+        b.iter(|| day1::top_three_calories_from_reader(black_box(data.as_bytes())))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, top_three);
+criterion_main!(benches);
@@ -1,3 +1,15 @@
+use aoc::error::{Error, Result};
+use aoc::parse::{self, Line};
+use aoc::solution::{parse_param, unknown_param, Answer, Solution};
+use rand::Rng;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+const DAY: u8 = 1;
+
 /*
 The Elves take turns writing down the number of Calories contained by the various meals, snacks,
 rations, etc. that they've brought with them, one item per line. Each Elf separates their own inventory
@@ -32,25 +44,15 @@ this is 24000 (carried by the fourth Elf).
 
 Find the Elf carrying the most Calories. How many total Calories is that Elf carrying?
 */
-pub fn max_calories(data: String) -> i32 {
-    let elfs_calories: Vec<&str> = data.split("\n\n").collect();
-    let mut max = 0;
-
-    for per_elf in &elfs_calories {
-        let calories: Vec<i32> = per_elf
-            .split('\n')
-            .filter(|cal| !cal.is_empty())
-            .map(|cal| cal.trim().parse::<i32>().unwrap())
-            .collect();
-
-        let sum: i32 = calories.iter().sum();
+pub fn max_calories(data: &str) -> Result<i64> {
+    Ok(max_of(&Inventory::parse(data, Arithmetic::Checked)?))
+}
 
-        if sum > max {
-            max = sum;
-        }
-    }
+/// Same as [`max_calories`], reading the input one line at a time without keeping it in memory
+pub fn max_calories_from_reader(reader: impl BufRead) -> Result<i64> {
+    let (best, _) = read_best_elves(reader, 1, Arithmetic::Checked)?;
 
-    max
+    Ok(best.first().map_or(0, |elf| elf.total as i64))
 }
 
 /*
@@ -63,89 +65,741 @@ they still have two backups.
 
 Find the top three Elves carrying the most Calories. How many Calories are those Elves carrying in total?
 */
-pub fn top_three_calories(data: String) -> i32 {
-    let elfs_calories: Vec<&str> = data.split("\n\n").collect();
-    let mut elfs_cal_sum: Vec<i32> = vec![];
-
-    for per_elf in &elfs_calories {
-        let calories: Vec<i32> = per_elf
-            .split('\n')
-            .filter(|cal| !cal.is_empty())
-            .map(|cal| cal.trim().parse::<i32>().unwrap())
+pub fn top_three_calories(data: &str) -> Result<i64> {
+    top_three_of(&Inventory::parse(data, Arithmetic::Checked)?)
+}
+
+/// Same as [`top_three_calories`], reading the input one line at a time without keeping it in
+/// memory
+pub fn top_three_calories_from_reader(reader: impl BufRead) -> Result<i64> {
+    let top = top_from_reader(reader, 3, Arithmetic::Checked)?;
+
+    Ok(sum_totals(&top, Arithmetic::Checked)? as i64)
+}
+
+/// Same as [`Inventory::top`] for the inventory read from `reader`, in a single pass keeping
+/// only the `k` best elves so far in memory
+pub fn top_from_reader(
+    reader: impl BufRead,
+    k: usize,
+    arithmetic: Arithmetic,
+) -> Result<Vec<Ranked>> {
+    let (best, elves) = read_best_elves(reader, k, arithmetic)?;
+
+    if elves < k {
+        return Err(too_few_elves(k, elves));
+    }
+    Ok(best)
+}
+
+/// Up to `k` elves carrying the most calories, along with the number of elves read
+fn read_best_elves(
+    reader: impl BufRead,
+    k: usize,
+    arithmetic: Arithmetic,
+) -> Result<(Vec<Ranked>, usize)> {
+    // Min-heap of the best elves, the worst of them on top: least calories then greatest index
+    let mut best: BinaryHeap<Reverse<(i128, Reverse<usize>)>> = BinaryHeap::with_capacity(k + 1);
+    let mut elves = 0;
+    let mut curr_elf: Option<i128> = None;
+
+    let items = parse::read_lines(DAY, reader, get_calories).chain([Ok(None)]);
+    for item in items {
+        let Some((line, calories)) = item? else {
+            if let Some(total) = curr_elf.take() {
+                best.push(Reverse((total, Reverse(elves))));
+                if best.len() > k {
+                    best.pop();
+                }
+                elves += 1;
+            }
+            continue;
+        };
+
+        let total = arithmetic.add(curr_elf.unwrap_or(0), calories.into());
+        curr_elf = Some(total.ok_or_else(|| overflow_error(elves, line))?);
+    }
+
+    let mut ranking: Vec<Ranked> = vec![];
+    for Reverse((total, Reverse(index))) in best.into_sorted_vec() {
+        let rank = match ranking.last() {
+            Some(previous) if previous.total == total => previous.rank,
+            _ => ranking.len() + 1,
+        };
+        ranking.push(Ranked { index, total, rank });
+    }
+
+    Ok((ranking, elves))
+}
+
+// Totals of checked inventories fit in 64 bits
+fn max_of(inventory: &Inventory) -> i64 {
+    inventory.max().map_or(0, |elf| elf.total as i64)
+}
+
+fn top_three_of(inventory: &Inventory) -> Result<i64> {
+    Ok(inventory.top_total(3)? as i64)
+}
+
+/// How the calories of the items are added up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Arithmetic {
+    /// Failing when a total does not fit in 64 bits
+    #[default]
+    Checked,
+    /// With 128 bits, so that any total of 64 bits items is right
+    Wide,
+}
+
+impl Arithmetic {
+    /// Adds the calories of an item to a total, `None` when the sum overflows
+    fn add(self, total: i128, calories: i128) -> Option<i128> {
+        let sum = total.checked_add(calories)?;
+
+        match self {
+            Arithmetic::Checked if i64::try_from(sum).is_err() => None,
+            _ => Some(sum),
+        }
+    }
+}
+
+impl FromStr for Arithmetic {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "checked" => Ok(Arithmetic::Checked),
+            "wide" => Ok(Arithmetic::Wide),
+            _ => Err("expected `checked` or `wide`".to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Arithmetic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arithmetic::Checked => write!(f, "checked"),
+            Arithmetic::Wide => write!(f, "wide"),
+        }
+    }
+}
+
+/// Items carried by an elf
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    /// Position of the elf in the inventory, starting at 0
+    pub index: usize,
+    /// Calories of each item
+    pub items: Vec<i64>,
+    pub total: i128,
+}
+
+/// Total calories of an elf along with its place among the other elves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ranked {
+    pub index: usize,
+    pub total: i128,
+    /// 1 for the elves carrying the most calories, the same for elves with the same total
+    pub rank: usize,
+}
+
+/// Header of the inventories written as CSV
+const CSV_HEADER: &str = "elf,item,calories";
+
+/// Formats inventories are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Calories of an item per line with blank lines between elves, like the puzzle input
+    Text,
+    /// `elf,item,calories` rows below a header, numbering elves and their items from 0
+    Csv,
+    /// Array with an array of the calories of the items of each elf
+    Json,
+}
+
+impl Format {
+    /// Format of `data` guessed from its first line, as only JSON starts with `[` and only CSV
+    /// has commas
+    pub fn detect(data: &str) -> Format {
+        let first = data.trim_start().lines().next().unwrap_or_default();
+
+        if first.starts_with('[') {
+            Format::Json
+        } else if first.contains(',') {
+            Format::Csv
+        } else {
+            Format::Text
+        }
+    }
+}
+
+/// Calories carried by every elf, in the order of the input
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    elves: Vec<Elf>,
+    arithmetic: Arithmetic,
+}
+
+impl Inventory {
+    /// Inventory of elves given by the calories of their items, failing when the total of an elf
+    /// overflows
+    pub fn new(elves: Vec<Vec<i64>>, arithmetic: Arithmetic) -> Result<Inventory> {
+        let items = elves.into_iter().flat_map(|items| {
+            items
+                .into_iter()
+                .map(|calories| Ok(Some((0, calories))))
+                .chain([Ok(None)])
+        });
+
+        collect_elves(items, arithmetic)
+    }
+
+    pub fn parse(data: &str, arithmetic: Arithmetic) -> Result<Inventory> {
+        collect_elves(parse::lines(DAY, data).map(get_calories), arithmetic)
+    }
+
+    /// Same as [`Inventory::parse`], reading the input one line at a time
+    pub fn from_reader(reader: impl BufRead, arithmetic: Arithmetic) -> Result<Inventory> {
+        collect_elves(parse::read_lines(DAY, reader, get_calories), arithmetic)
+    }
+
+    /// Parses an inventory written in the given format
+    pub fn parse_as(data: &str, format: Format, arithmetic: Arithmetic) -> Result<Inventory> {
+        match format {
+            Format::Text => Inventory::parse(data, arithmetic),
+            Format::Csv => parse_csv(data, arithmetic),
+            Format::Json => parse_json(data, arithmetic),
+        }
+    }
+
+    /// Writes the inventory in the given format, so that [`Inventory::parse_as`] reads it back
+    pub fn write(&self, format: Format) -> String {
+        let items = |elf: &Elf, separator| {
+            let items: Vec<String> = elf.items.iter().map(|c| c.to_string()).collect();
+            items.join(separator)
+        };
+
+        match format {
+            Format::Text => {
+                let elves: Vec<String> = self.elves.iter().map(|e| items(e, "\n") + "\n").collect();
+                elves.join("\n")
+            }
+            Format::Csv => {
+                let mut csv = format!("{}\n", CSV_HEADER);
+                for elf in &self.elves {
+                    for (item, calories) in elf.items.iter().enumerate() {
+                        csv += &format!("{},{},{}\n", elf.index, item, calories);
+                    }
+                }
+                csv
+            }
+            Format::Json => {
+                let elves: Vec<String> = self
+                    .elves
+                    .iter()
+                    .map(|e| format!("[{}]", items(e, ",")))
+                    .collect();
+                format!("[{}]\n", elves.join(","))
+            }
+        }
+    }
+
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    pub fn len(&self) -> usize {
+        self.elves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elves.is_empty()
+    }
+
+    /// Total calories of each elf, in the order of the inventory
+    pub fn totals(&self) -> Vec<i128> {
+        self.elves.iter().map(|e| e.total).collect()
+    }
+
+    /// Every elf from the most calories to the least, elves with the same total being ordered by
+    /// index
+    pub fn ranking(&self) -> Vec<Ranked> {
+        let mut totals: Vec<(usize, i128)> =
+            self.elves.iter().map(|e| (e.index, e.total)).collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut ranking: Vec<Ranked> = Vec::with_capacity(totals.len());
+        for (i, (index, total)) in totals.into_iter().enumerate() {
+            let rank = match ranking.last() {
+                Some(previous) if previous.total == total => previous.rank,
+                _ => i + 1,
+            };
+            ranking.push(Ranked { index, total, rank });
+        }
+
+        ranking
+    }
+
+    /// Elf carrying the most calories, the first one when several carry as much
+    pub fn max(&self) -> Option<Ranked> {
+        self.ranking().first().copied()
+    }
+
+    /// The `k` elves carrying the most calories, failing when there are fewer than `k` elves
+    pub fn top(&self, k: usize) -> Result<Vec<Ranked>> {
+        if self.elves.len() < k {
+            return Err(too_few_elves(k, self.elves.len()));
+        }
+
+        let mut ranking = self.ranking();
+        ranking.truncate(k);
+        Ok(ranking)
+    }
+
+    /// Same as [`Inventory::top`], along with the elves tied with the last one
+    pub fn top_with_ties(&self, k: usize) -> Vec<Ranked> {
+        let ranking = self.ranking();
+        let Some(last) = ranking.get(k.max(1) - 1).copied() else {
+            return ranking;
+        };
+
+        ranking
+            .into_iter()
+            .take_while(|elf| elf.rank <= last.rank)
+            .collect()
+    }
+
+    /// Calories carried by the `k` elves carrying the most
+    pub fn top_total(&self, k: usize) -> Result<i128> {
+        sum_totals(&self.top(k)?, self.arithmetic)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.elves.is_empty() {
+            return None;
+        }
+
+        let sum: f64 = self.totals().iter().map(|t| *t as f64).sum();
+        Some(sum / self.elves.len() as f64)
+    }
+
+    /// Middle total, or the mean of the two middle ones for an even number of elves
+    pub fn median(&self) -> Option<f64> {
+        let mut totals = self.totals();
+        totals.sort_unstable();
+
+        let middle = totals.len() / 2;
+        match totals.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(totals[middle] as f64),
+            _ => Some((totals[middle - 1] as f64 + totals[middle] as f64) / 2.0),
+        }
+    }
+
+    /// Elf at the given percentile, from 0 to 100, of the totals sorted from the least calories
+    /// to the most, using the nearest rank
+    pub fn percentile(&self, percentile: f64) -> Option<Ranked> {
+        if !(0.0..=100.0).contains(&percentile) {
+            return None;
+        }
+
+        let ranking = self.ranking();
+        let position = (percentile / 100.0 * ranking.len() as f64).ceil() as usize;
+        ranking
+            .get(ranking.len().checked_sub(position.max(1))?)
+            .copied()
+    }
+}
+
+/// Number of the line and calories of the item on it, or `None` for the blank lines between
+/// elves
+fn get_calories(line: Line) -> Result<Option<(usize, i64)>> {
+    let calories = line.text.trim();
+
+    if calories.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some((line.number, line.parse(calories)?)))
+    }
+}
+
+/// Groups the items into elves, the line numbers of the items only being used to report the
+/// totals that overflow
+fn collect_elves(
+    items: impl Iterator<Item = Result<Option<(usize, i64)>>>,
+    arithmetic: Arithmetic,
+) -> Result<Inventory> {
+    let mut elves: Vec<Elf> = vec![];
+    let mut curr_elf: Option<Elf> = None;
+
+    for item in items {
+        let Some((line, calories)) = item? else {
+            elves.extend(curr_elf.take());
+            continue;
+        };
+
+        let index = elves.len();
+        let elf = curr_elf.get_or_insert_with(|| Elf {
+            index,
+            items: vec![],
+            total: 0,
+        });
+        elf.items.push(calories);
+        elf.total = arithmetic
+            .add(elf.total, calories.into())
+            .ok_or_else(|| overflow_error(index, line))?;
+    }
+    elves.extend(curr_elf);
+
+    Ok(Inventory { elves, arithmetic })
+}
+
+/// Parses the rows of a CSV inventory, which must list the elves and their items in order
+fn parse_csv(data: &str, arithmetic: Arithmetic) -> Result<Inventory> {
+    let mut lines = parse::lines(DAY, data).filter(|l| !l.text.trim().is_empty());
+    match lines.next() {
+        Some(line) if line.text.trim() == CSV_HEADER => {}
+        Some(line) => {
+            return Err(line.error(1, format!("expected the header `{}`", CSV_HEADER)));
+        }
+        None => return collect_elves(std::iter::empty(), arithmetic),
+    }
+
+    let mut items: Vec<Result<Option<(usize, i64)>>> = vec![];
+    // Elf and item expected on the next row, unless it starts the next elf
+    let mut next: (usize, usize) = (0, 0);
+
+    for line in lines {
+        let [elf, item, calories] = line.split(line.text, ',', "expected `elf,item,calories`")?;
+        let position = (line.parse(elf.trim())?, line.parse(item.trim())?);
+
+        if next.1 > 0 && position == (next.0 + 1, 0) {
+            items.push(Ok(None));
+            next = position;
+        }
+        if position != next {
+            let expected = match next {
+                (elf, 0) => format!("expected item 0 of elf {}", elf),
+                (elf, item) => format!(
+                    "expected item {} of elf {} or item 0 of elf {}",
+                    item,
+                    elf,
+                    elf + 1
+                ),
+            };
+            return Err(line.error_at(elf, expected));
+        }
+
+        items.push(Ok(Some((line.number, line.parse(calories.trim())?))));
+        next.1 += 1;
+    }
+
+    collect_elves(items.into_iter(), arithmetic)
+}
+
+/// Parses a JSON inventory, where every elf must carry at least one item
+fn parse_json(data: &str, arithmetic: Arithmetic) -> Result<Inventory> {
+    let elves: Vec<Vec<i64>> = serde_json::from_str(data).map_err(|err| {
+        let message = err.to_string();
+        let message = message.split(" at line ").next().unwrap_or_default();
+
+        // serde_json counts lines from 1, a 0 meaning that the error has no position
+        match err.line() {
+            0 => Error::new(DAY, message),
+            line => {
+                let text = data.lines().nth(line - 1).unwrap_or_default();
+                Error::at(DAY, line, text, err.column().max(1), message)
+            }
+        }
+    })?;
+
+    if let Some(index) = elves.iter().position(Vec::is_empty) {
+        return Err(Error::new(
+            DAY,
+            format!("elf {} does not carry any item", index + 1),
+        ));
+    }
+
+    Inventory::new(elves, arithmetic)
+}
+
+/// Error for the elf at `index` whose total overflows when adding the item on `line`, 0 when the
+/// item does not come from an input line
+fn overflow_error(index: usize, line: usize) -> Error {
+    Error {
+        line: (line > 0).then_some(line),
+        ..Error::new(DAY, format!("the calories of elf {} overflow", index + 1))
+    }
+}
+
+fn sum_totals(elves: &[Ranked], arithmetic: Arithmetic) -> Result<i128> {
+    elves
+        .iter()
+        .try_fold(0, |sum, elf| arithmetic.add(sum, elf.total))
+        .ok_or_else(|| {
+            Error::new(
+                DAY,
+                format!("the calories of the top {} elves overflow", elves.len()),
+            )
+        })
+}
+
+fn too_few_elves(k: usize, found: usize) -> Error {
+    Error::new(
+        DAY,
+        format!("expected at least {} elves, found {}", k, found),
+    )
+}
+
+/// Random puzzle input with `size` elves, at least three, sometimes separated by more than one
+/// blank line
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut data = String::new();
+
+    for i in 0..size.max(3) {
+        if i > 0 {
+            data += if rng.gen_bool(0.05) { "\n\n\n" } else { "\n\n" };
+        }
+
+        let items: Vec<String> = (0..rng.gen_range(1..=15))
+            .map(|_| rng.gen_range(1000..=70000).to_string())
             .collect();
+        data += &items.join("\n");
+    }
 
-        let sum: i32 = calories.iter().sum();
+    data + "\n"
+}
+
+#[derive(Default)]
+pub struct Day1 {
+    inventory: Inventory,
+    arithmetic: Arithmetic,
+}
+
+impl Solution for Day1 {
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.inventory = Inventory::parse_as(data, Format::detect(data), self.arithmetic)?;
+        Ok(())
+    }
 
-        elfs_cal_sum.push(sum)
+    fn part1(&self) -> Result<Answer> {
+        Ok(self.inventory.max().map_or(0, |elf| elf.total).into())
     }
 
-    elfs_cal_sum.sort_by(|a, b| b.cmp(a));
+    fn part2(&self) -> Result<Answer> {
+        Ok(self.inventory.top_total(3)?.into())
+    }
 
-    elfs_cal_sum[..3].iter().sum()
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![("arithmetic", self.arithmetic.to_string())]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "arithmetic" => self.arithmetic = parse_param(DAY, name, value)?,
+            _ => return Err(unknown_param(DAY, name)),
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     struct TestCase {
         input: String,
-        expected: i32,
+        expected: i64,
     }
 
     #[test]
     fn it_should_return_max_calories() {
-        let contents = String::from(
-            "1000
-2000
-3000
-
-4000 
+        let contents = String::from(include_str!("../data/examples/day1/example.txt"));
+        let test_case = TestCase {
+            input: contents,
+            expected: 24000,
+        };
 
-5000
-6000
+        let result = max_calories(&test_case.input).unwrap();
 
-7000
-8000
-9000
+        assert_eq!(result, test_case.expected);
+    }
 
-10000",
-        );
+    #[test]
+    fn it_should_return_top_three_calories_count() {
+        let contents = String::from(include_str!("../data/examples/day1/example.txt"));
         let test_case = TestCase {
             input: contents,
-            expected: 24000,
+            expected: 45000,
         };
 
-        let result = max_calories(test_case.input);
+        let result = top_three_calories(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
 
     #[test]
-    fn it_should_return_top_three_calories_count() {
-        let contents = String::from(
-            "1000
-2000
-3000
+    fn it_should_read_calories_from_a_reader() {
+        let contents = include_str!("../data/examples/day1/example.txt");
 
-4000 
+        assert_eq!(max_calories_from_reader(contents.as_bytes()), Ok(24000));
+        assert_eq!(
+            top_three_calories_from_reader(contents.as_bytes()),
+            Ok(45000)
+        );
+    }
 
-5000
-6000
+    #[test]
+    fn it_should_report_invalid_calories() {
+        let result = max_calories("1000\n\n20x0\n3000");
 
-7000
-8000
-9000
+        let error = result.unwrap_err();
+        assert_eq!((error.line, error.column), (Some(3), Some(1)));
+    }
 
-10000",
+    #[test]
+    fn it_should_require_three_elves_for_top_three() {
+        let result = top_three_calories("1000\n\n2000");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_rank_elves_and_summarize_totals() {
+        let data = include_str!("../data/examples/day1/example.txt");
+        let inventory = Inventory::parse(data, Arithmetic::Checked).unwrap();
+        let ranked = |index, total, rank| Ranked { index, total, rank };
+
+        assert_eq!(inventory.totals(), [6000, 4000, 11000, 24000, 10000]);
+        assert_eq!(
+            inventory.top(2),
+            Ok(vec![ranked(3, 24000, 1), ranked(2, 11000, 2)])
         );
-        let test_case = TestCase {
-            input: contents,
-            expected: 45000,
-        };
+        assert!(inventory.top(6).is_err());
+        assert_eq!(inventory.mean(), Some(11000.0));
+        assert_eq!(inventory.median(), Some(10000.0));
+        assert_eq!(inventory.percentile(90.0), Some(ranked(3, 24000, 1)));
+        assert_eq!(inventory.percentile(0.0), Some(ranked(1, 4000, 5)));
+    }
 
-        let result = top_three_calories(test_case.input);
+    #[test]
+    fn it_should_share_ranks_between_tied_elves() {
+        let elves = vec![vec![1], vec![2], vec![1], vec![1, 1]];
+        let inventory = Inventory::new(elves, Arithmetic::Checked).unwrap();
+
+        let ranks: Vec<(usize, usize)> = inventory
+            .ranking()
+            .iter()
+            .map(|e| (e.index, e.rank))
+            .collect();
 
-        assert_eq!(result, test_case.expected);
+        assert_eq!(ranks, [(1, 1), (3, 1), (0, 3), (2, 3)]);
+        assert_eq!(inventory.top_with_ties(1).len(), 2);
+        assert_eq!(inventory.top_with_ties(3).len(), 4);
+        assert_eq!(inventory.median(), Some(1.5));
+    }
+
+    #[test]
+    fn it_should_report_overflowing_elves_unless_wide() {
+        let data = "1\n\n9223372036854775807\n1\n\n5";
+
+        let error = Inventory::parse(data, Arithmetic::Checked).unwrap_err();
+        let wide = Inventory::parse(data, Arithmetic::Wide).unwrap();
+
+        assert_eq!(error.line, Some(4));
+        assert_eq!(error.message, "the calories of elf 2 overflow");
+        assert_eq!(wide.top_total(2), Ok(9223372036854775813));
+        assert!(max_calories(data).is_err());
+    }
+
+    #[test]
+    fn it_should_stream_the_top_elves() {
+        let data = "1\n\n5\n\n\n3\n\n5\n\n2\n";
+        let inventory = Inventory::parse(data, Arithmetic::Checked).unwrap();
+
+        for k in 0..=5 {
+            let streamed = top_from_reader(data.as_bytes(), k, Arithmetic::Checked);
+
+            assert_eq!(streamed, inventory.top(k));
+        }
+        assert!(top_from_reader(data.as_bytes(), 6, Arithmetic::Checked).is_err());
+        assert_eq!(max_calories_from_reader("".as_bytes()), Ok(0));
+    }
+
+    #[test]
+    fn it_should_convert_inventories_between_formats() {
+        let data = include_str!("../data/examples/day1/example.txt");
+        let inventory = Inventory::parse(data, Arithmetic::Checked).unwrap();
+
+        for format in [Format::Text, Format::Csv, Format::Json] {
+            let written = inventory.write(format);
+            let read = Inventory::parse_as(&written, Format::detect(&written), Arithmetic::Checked);
+
+            assert_eq!(Format::detect(&written), format);
+            assert_eq!(read, Ok(inventory.clone()));
+        }
+        assert_eq!(
+            inventory.write(Format::Json),
+            "[[1000,2000,3000],[4000],[5000,6000],[7000,8000,9000],[10000]]\n"
+        );
+    }
+
+    #[test]
+    fn it_should_report_invalid_csv_and_json_inventories() {
+        let header = Inventory::parse_as("elf,calories\n0,1000", Format::Csv, Arithmetic::Checked);
+        let gap = Inventory::parse_as(
+            "elf,item,calories\n0,0,1000\n2,0,2000",
+            Format::Csv,
+            Arithmetic::Checked,
+        );
+        let json = Inventory::parse_as("[[1000],\n [2000, x]]", Format::Json, Arithmetic::Checked);
+        let empty = Inventory::parse_as("[[1000], []]", Format::Json, Arithmetic::Checked);
+        let eof = Inventory::parse_as("", Format::Json, Arithmetic::Checked);
+
+        assert_eq!(header.unwrap_err().line, Some(1));
+        assert_eq!(
+            gap.unwrap_err().message,
+            "expected item 1 of elf 0 or item 0 of elf 1"
+        );
+        assert_eq!(
+            json.map_err(|e| (e.line, e.column)),
+            Err((Some(2), Some(9)))
+        );
+        assert!(empty.is_err());
+        assert_eq!(eof.map_err(|e| (e.line, e.column)), Err((Some(1), Some(1))));
+    }
+
+    /// Calories carried by each elf, along with the input listing them
+    fn elves() -> impl Strategy<Value = (Vec<Vec<i32>>, String)> {
+        prop::collection::vec(prop::collection::vec(1..100_000i32, 1..6), 3..20).prop_map(|elves| {
+            let data = elves
+                .iter()
+                .map(|elf| {
+                    elf.iter()
+                        .map(|c| c.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            (elves, data)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_calories((elves, data) in elves()) {
+            let mut sums: Vec<i64> = elves.iter().map(|elf| elf.iter().map(|c| i64::from(*c)).sum()).collect();
+            sums.sort_unstable_by(|a, b| b.cmp(a));
+
+            prop_assert_eq!(max_calories(&data), Ok(sums[0]));
+            prop_assert_eq!(top_three_calories(&data), Ok(sums[..3].iter().sum()));
+            prop_assert_eq!(top_three_calories_from_reader(data.as_bytes()), Ok(sums[..3].iter().sum()));
+        }
     }
 }
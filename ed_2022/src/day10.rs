@@ -1,63 +1,102 @@
-#[derive(Debug)]
+use aoc::error::{Error, Result};
+use aoc::parse::{self, Line};
+use aoc::solution::{Answer, Solution};
+use rand::Rng;
+use std::io::BufRead;
+
+const DAY: u8 = 10;
+
+#[derive(Debug, Clone)]
 struct Instruction {
     amount: i64,
     cycles: i64,
+    /// Number and text of the line, to report the values overflowing while it runs
+    line: usize,
+    text: String,
+}
+
+impl Instruction {
+    /// Error pointing at the last word of the instruction, the amount of an `addx`
+    fn error(&self, message: &str) -> Error {
+        let part = self.text.rsplit(' ').next().unwrap_or(&self.text);
+
+        Error::at_part(DAY, self.line, &self.text, part, message)
+    }
+}
+
+pub fn calculate_signal_strengths(data: &str) -> Result<i64> {
+    signal_strengths(parse::lines(DAY, data).map(get_instruction))
 }
 
-pub fn calculate_signal_strengths(data: String) -> i64 {
-    let instructions: Vec<Instruction> = get_instructions(data);
+/// Same as [`calculate_signal_strengths`], reading the program one line at a time
+pub fn calculate_signal_strengths_from_reader(reader: impl BufRead) -> Result<i64> {
+    signal_strengths(parse::read_lines(DAY, reader, get_instruction))
+}
+
+pub fn get_crt_pixels(data: &str) -> Result<String> {
+    crt_pixels(parse::lines(DAY, data).map(get_instruction))
+}
+
+/// Same as [`get_crt_pixels`], reading the program one line at a time
+pub fn get_crt_pixels_from_reader(reader: impl BufRead) -> Result<String> {
+    crt_pixels(parse::read_lines(DAY, reader, get_instruction))
+}
 
-    let mut instr_idx = 0;
+fn signal_strengths(instructions: impl IntoIterator<Item = Result<Instruction>>) -> Result<i64> {
+    let mut instructions = instructions.into_iter();
+    let mut instruction = instructions.next().transpose()?;
     let mut cycle = 1;
     let mut cycles_processed = 1;
     let mut strength = 0;
-    let mut register = 1;
+    let mut register: i64 = 1;
     let signals = [20, 60, 100, 140, 180, 220];
 
-    while let Some(i) = instructions.get(instr_idx) {
+    while let Some(i) = &instruction {
         if signals.contains(&cycle) {
-            strength += register * cycle;
+            strength = register
+                .checked_mul(cycle)
+                .and_then(|signal| signal.checked_add(strength))
+                .ok_or_else(|| i.error("the signal strength overflows"))?;
         }
 
         if i.cycles == cycles_processed {
-            instr_idx += 1;
             cycles_processed = 0;
-            register += i.amount;
+            register = register
+                .checked_add(i.amount)
+                .ok_or_else(|| i.error("the register overflows"))?;
+            instruction = instructions.next().transpose()?;
         }
 
         cycle += 1;
         cycles_processed += 1;
     }
 
-    strength
+    Ok(strength)
 }
 
-pub fn get_crt_pixels(data: String) -> String {
-    let instructions: Vec<Instruction> = get_instructions(data);
+fn crt_pixels(instructions: impl IntoIterator<Item = Result<Instruction>>) -> Result<String> {
     let crt_max_cols = 40;
     let crt_max_rows = 6;
-    let mut instr_idx = 0;
+    let mut instructions = instructions.into_iter();
+    let mut instruction = instructions.next().transpose()?;
     let mut cycle = 1;
     let mut cycles_processed = 1;
-    let mut register = 1;
+    let mut register: i64 = 1;
     let mut crt_screen: Vec<Vec<&str>> = vec![vec!["#"]];
     let mut curr_row = 0;
 
-    while let Some(v) = instructions.get(instr_idx) {
+    while let Some(v) = &instruction {
         if v.cycles == cycles_processed {
-            instr_idx += 1;
             cycles_processed = 0;
-            register += v.amount;
+            register = register
+                .checked_add(v.amount)
+                .ok_or_else(|| v.error("the register overflows"))?;
+            instruction = instructions.next().transpose()?;
         }
 
         let offset = curr_row * crt_max_cols;
-        let cursor = [
-            &register - 1 + offset,
-            register + offset,
-            &register + 1 + offset,
-        ];
 
-        if cursor.contains(&cycle) {
+        if register.abs_diff(cycle - offset) <= 1 {
             crt_screen[curr_row as usize].push("#");
         } else {
             crt_screen[curr_row as usize].push(".");
@@ -77,31 +116,80 @@ pub fn get_crt_pixels(data: String) -> String {
         }
     }
 
-    crt_screen.iter().map(|r| r.join("")).collect()
+    Ok(crt_screen.iter().map(|r| r.join("")).collect())
 }
 
-fn get_instructions(data: String) -> Vec<Instruction> {
-    data.lines()
-        .map(|l| {
-            let parts: Vec<&str> = l.split(' ').collect();
-            match parts[0] {
-                "addx" => Instruction {
-                    amount: parts[1].parse::<i64>().unwrap(),
-                    cycles: 2,
-                },
-                "noop" => Instruction {
-                    amount: 0,
-                    cycles: 1,
-                },
-                _ => panic!("instruction not supported"),
-            }
-        })
-        .collect()
+fn get_instructions(data: &str) -> Result<Vec<Instruction>> {
+    parse::lines(DAY, data).map(get_instruction).collect()
+}
+
+fn get_instruction(line: Line) -> Result<Instruction> {
+    let parts: Vec<&str> = line.text.split(' ').collect();
+    match parts[..] {
+        ["addx", amount] => Ok(Instruction {
+            amount: line.parse(amount)?,
+            cycles: 2,
+            line: line.number,
+            text: line.text.to_owned(),
+        }),
+        ["noop"] => Ok(Instruction {
+            amount: 0,
+            cycles: 1,
+            line: line.number,
+            text: line.text.to_owned(),
+        }),
+        _ => Err(line.error(1, "instruction not supported")),
+    }
+}
+
+/// Random puzzle input with `size` instructions, or more to last the 240 cycles drawn on the
+/// screen
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut data = String::new();
+    let (mut instructions, mut cycles) = (0, 0);
+
+    while instructions < size || cycles < 240 {
+        if rng.gen_bool(0.3) {
+            data += "noop\n";
+            cycles += 1;
+        } else {
+            data += &format!("addx {}\n", rng.gen_range(-20..=20));
+            cycles += 2;
+        }
+        instructions += 1;
+    }
+
+    data
+}
+
+#[derive(Default)]
+pub struct Day10 {
+    instructions: Vec<Instruction>,
+}
+
+impl Solution for Day10 {
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.instructions = get_instructions(data)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(signal_strengths(self.instructions.iter().cloned().map(Ok))?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(crt_pixels(self.instructions.iter().cloned().map(Ok))?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     struct TestCase {
         input: String,
@@ -109,154 +197,7 @@ mod tests {
     }
 
     fn get_data() -> String {
-        String::from(
-            "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop",
-        )
+        String::from(include_str!("../data/examples/day10/example.txt"))
     }
 
     #[test]
@@ -266,7 +207,7 @@ noop",
             expected: 13140,
         };
 
-        let result = calculate_signal_strengths(test_case.input);
+        let result = calculate_signal_strengths(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
@@ -282,8 +223,95 @@ noop",
 #######.......#######.......#######.....
 ";
 
-        let result = get_crt_pixels(input);
+        let result = get_crt_pixels(&input).unwrap();
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn it_should_read_the_program_from_a_reader() {
+        let contents = include_str!("../data/examples/day10/example.txt");
+
+        let pixels = get_crt_pixels_from_reader(contents.as_bytes()).unwrap();
+
+        assert_eq!(
+            calculate_signal_strengths_from_reader(contents.as_bytes()),
+            Ok(13140)
+        );
+        assert_eq!(pixels, get_crt_pixels(contents).unwrap());
+    }
+
+    #[test]
+    fn it_should_report_unsupported_instructions() {
+        let unsupported = calculate_signal_strengths("noop\nsubx 3").unwrap_err();
+        let amount = calculate_signal_strengths("addx +-3").unwrap_err();
+
+        assert_eq!(unsupported.line, Some(2));
+        assert_eq!((amount.line, amount.column), (Some(1), Some(6)));
+    }
+
+    #[test]
+    fn it_should_report_overflows() {
+        let register =
+            calculate_signal_strengths("noop\naddx 9223372036854775807\naddx 1").unwrap_err();
+        let program = format!("addx 4611686018427387904{}", "\nnoop".repeat(20));
+        let strength = calculate_signal_strengths(&program).unwrap_err();
+        let pixels = get_crt_pixels("addx 9223372036854775807\naddx 1").unwrap_err();
+
+        assert_eq!((register.line, register.column), (Some(2), Some(6)));
+        assert_eq!(register.message, "the register overflows");
+        assert_eq!(strength.message, "the signal strength overflows");
+        assert_eq!(pixels.line, Some(1));
+    }
+
+    /// Programs lasting at least the 240 cycles drawn on the screen, where `None` is a `noop`
+    fn programs() -> impl Strategy<Value = Vec<Option<i64>>> {
+        prop::collection::vec(prop::option::of(-20..20i64), 240..300)
+    }
+
+    /// Value of the register during each cycle
+    fn reference_cycles(program: &[Option<i64>]) -> Vec<i64> {
+        let mut register = 1;
+        let mut cycles = vec![];
+
+        for instruction in program {
+            cycles.push(register);
+            if let Some(amount) = instruction {
+                cycles.push(register);
+                register += amount;
+            }
+        }
+
+        cycles
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_cpu(program in programs()) {
+            let data = program
+                .iter()
+                .map(|i| i.map_or("noop".to_owned(), |amount| format!("addx {}", amount)))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let cycles = reference_cycles(&program);
+            let strength: i64 = (20..=220).step_by(40).map(|c| c as i64 * cycles[c - 1]).sum();
+            let pixels: String = cycles[..240]
+                .chunks(40)
+                .map(|row| {
+                    let row: String = row
+                        .iter()
+                        .enumerate()
+                        .map(|(x, register)| {
+                            if (register - x as i64).abs() <= 1 { '#' } else { '.' }
+                        })
+                        .collect();
+                    row + "\n"
+                })
+                .collect();
+
+            prop_assert_eq!(calculate_signal_strengths(&data), Ok(strength));
+            prop_assert_eq!(get_crt_pixels(&data), Ok(pixels));
+        }
+    }
 }
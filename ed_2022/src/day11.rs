@@ -1,3 +1,13 @@
+use aoc::error::{Error, Result};
+use aoc::parse::{self, Line};
+use aoc::solution::{parse_param, parse_param_in, unknown_param, Answer, Solution};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+const DAY: u8 = 11;
+/// Most rounds the monkeys can be given through the parameters, keeping a run short
+const MAX_ROUNDS: u64 = 100_000;
+
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 enum OpType {
     Add,
@@ -28,8 +38,30 @@ pub struct Test {
     throw_false: usize,
 }
 
-pub fn monkey_business_level(monkeys: &mut [Monkey], rounds: u64, with_relief: bool) -> u64 {
-    for _ in 0..rounds {
+/// Line of a throw, along with the monkey it targets
+type Throw<'a> = (Line<'a>, usize);
+
+pub fn monkey_business_level(
+    monkeys: &mut [Monkey],
+    rounds: u64,
+    with_relief: bool,
+) -> Result<u64> {
+    if monkeys.len() < 2 {
+        return Err(Error::new(
+            DAY,
+            format!(
+                "monkey business needs at least two monkeys, found {}",
+                monkeys.len()
+            ),
+        ));
+    }
+
+    let divisors_product = monkeys
+        .iter()
+        .try_fold(1u64, |acc, m| acc.checked_mul(m.test.divisible_by))
+        .ok_or_else(|| Error::new(DAY, "the product of the divisors overflows"))?;
+
+    for round in 0..rounds {
         let monkeys_clone = monkeys.to_owned();
         for (i, monkey) in monkeys_clone.iter().enumerate() {
             while !monkeys[i].items.is_empty() {
@@ -45,16 +77,26 @@ pub fn monkey_business_level(monkeys: &mut [Monkey], rounds: u64, with_relief: b
                 };
 
                 let new_item = match monkey.operation.op_type {
-                    OpType::Add => left_op + right_op,
-                    OpType::Substract => left_op - right_op,
-                    OpType::Multiply => left_op * right_op,
-                    OpType::Divide => left_op / right_op,
-                };
+                    OpType::Add => left_op.checked_add(right_op),
+                    OpType::Substract => left_op.checked_sub(right_op),
+                    OpType::Multiply => left_op.checked_mul(right_op),
+                    OpType::Divide => left_op.checked_div(right_op),
+                }
+                .ok_or_else(|| {
+                    Error::new(
+                        DAY,
+                        format!(
+                            "worry level of monkey {} is out of range in round {}",
+                            i,
+                            round + 1
+                        ),
+                    )
+                })?;
 
                 let new_worry_level = if with_relief {
-                    ((new_item as f64) / 3.0).floor() as u64
+                    new_item / 3
                 } else {
-                     new_item % monkeys.iter().map(|m| m.test.divisible_by).product::<u64>()
+                    new_item % divisors_product
                 };
 
                 if new_worry_level % monkey.test.divisible_by == 0 {
@@ -68,80 +110,259 @@ pub fn monkey_business_level(monkeys: &mut [Monkey], rounds: u64, with_relief: b
         }
     }
 
-    monkeys.sort_by(|a, b| b.inspected_count.cmp(&a.inspected_count));
-    monkeys.iter().take(2).map(|m| m.inspected_count).product()
+    monkeys.sort_by_key(|m| std::cmp::Reverse(m.inspected_count));
+    Ok(monkeys.iter().take(2).map(|m| m.inspected_count).product())
 }
 
-pub fn parse_input(data: String) -> Vec<Monkey> {
-    data.split("\n\n")
-        .into_iter()
-        .map(|b| {
-            let lines = b.lines().collect::<Vec<&str>>();
-
-            let items = lines[1]
-                .strip_prefix("  Starting items: ")
-                .unwrap()
-                .split(", ")
-                .into_iter()
-                .map(|n| n.parse::<u64>().unwrap())
-                .collect();
-
-            let op_parts: Vec<&str> = lines[2]
-                .strip_prefix("  Operation: new = ")
-                .unwrap()
-                .split(' ')
-                .collect();
-            let operation = Operation {
-                op_type: match op_parts[1] {
-                    "+" => OpType::Add,
-                    "-" => OpType::Substract,
-                    "*" => OpType::Multiply,
-                    "/" => OpType::Divide,
-                    _ => panic!("Operation not allowed"),
-                },
-                left: match op_parts[0].parse::<u64>() {
-                    Ok(v) => Some(v),
-                    Err(_) => None,
-                },
-                right: match op_parts[2].parse::<u64>() {
-                    Ok(v) => Some(v),
-                    Err(_) => None,
-                },
-            };
+pub fn parse_input(data: &str) -> Result<Vec<Monkey>> {
+    let mut monkeys: Vec<Monkey> = vec![];
+    let mut throws: Vec<(Line, usize, usize)> = vec![];
+
+    for block in parse::blocks(DAY, data) {
+        let (monkey, monkey_throws) = parse_monkey(&block)?;
 
-            let divisible_by = lines[3]
-                .strip_prefix("  Test: divisible by ")
-                .unwrap()
-                .parse::<u64>()
-                .unwrap();
-            let throw_true = lines[4]
-                .strip_prefix("    If true: throw to monkey ")
-                .unwrap()
-                .parse::<usize>()
-                .unwrap();
-            let throw_false = lines[5]
-                .strip_prefix("    If false: throw to monkey ")
-                .unwrap()
-                .parse::<usize>()
-                .unwrap();
+        throws.extend(monkey_throws.map(|(line, target)| (line, target, monkeys.len())));
+        monkeys.push(monkey);
+    }
+
+    for (line, target, i) in throws {
+        if target >= monkeys.len() || target == i {
+            return Err(line.error(1, format!("monkey {} cannot throw to monkey {}", i, target)));
+        }
+    }
+
+    Ok(monkeys)
+}
+
+/// Parses the lines describing one monkey.
+/// Returns the monkey along with its throw lines and the monkeys they target.
+fn parse_monkey<'a>(block: &[Line<'a>]) -> Result<(Monkey, [Throw<'a>; 2])> {
+    let field = |n: usize, prefix: &str| -> Result<(Line<'a>, &'a str)> {
+        let Some(line) = block.get(n) else {
+            return Err(
+                block[0].error(1, format!("monkey is missing its `{}` line", prefix.trim()))
+            );
+        };
+
+        Ok((*line, line.field(prefix)?))
+    };
+
+    field(0, "Monkey ")?;
+
+    let (items_line, items) = field(1, "  Starting items:")?;
+    let items = items
+        .split(',')
+        .map(|n| n.trim())
+        .filter(|n| !n.is_empty())
+        .map(|n| items_line.parse(n))
+        .collect::<Result<Vec<u64>>>()?;
+
+    let (op_line, op) = field(2, "  Operation: new =")?;
+    let [left, op_type, right] = op_line.split(op, ' ', "expected an operation like `old * 19`")?;
+    let operand = |part: &str| -> Result<Option<u64>> {
+        match part {
+            "old" => Ok(None),
+            _ => op_line.parse(part).map(Some),
+        }
+    };
+    let operation = Operation {
+        op_type: match op_type {
+            "+" => OpType::Add,
+            "-" => OpType::Substract,
+            "*" => OpType::Multiply,
+            "/" => OpType::Divide,
+            _ => return Err(op_line.error_at(op_type, "Operation not allowed")),
+        },
+        left: operand(left)?,
+        right: operand(right)?,
+    };
+    if operation.op_type == OpType::Divide && operation.right == Some(0) {
+        return Err(op_line.error_at(right, "division by zero"));
+    }
+
+    let (test_line, divisible_by) = field(3, "  Test: divisible by")?;
+    let divisible_by: u64 = test_line.parse(divisible_by)?;
+    if divisible_by == 0 {
+        return Err(test_line.error(1, "cannot test divisibility by 0"));
+    }
+
+    let (true_line, throw_true) = field(4, "    If true: throw to monkey")?;
+    let (false_line, throw_false) = field(5, "    If false: throw to monkey")?;
+
+    let throw_true: usize = true_line.parse(throw_true)?;
+    let throw_false: usize = false_line.parse(throw_false)?;
+
+    let monkey = Monkey {
+        items,
+        operation,
+        test: Test {
+            divisible_by,
+            throw_true,
+            throw_false,
+        },
+        inspected_count: 0,
+    };
+
+    Ok((monkey, [(true_line, throw_true), (false_line, throw_false)]))
+}
+
+/// Random puzzle input with `size` items, at least one, spread among three to eight monkeys. One
+/// of them squares the worry levels and never gets items from the others. Items whose worry level
+/// would still overflow within the 20 rounds of part 1 are left out.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let count = (size / 4).clamp(3, 8);
+    let mut divisors: Vec<u64> = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+    divisors.shuffle(rng);
+    let squaring = rng.gen_range(0..count);
+
+    let mut monkeys: Vec<Monkey> = (0..count)
+        .map(|i| {
+            let mut others: Vec<usize> = (0..count).filter(|m| *m != i && *m != squaring).collect();
+            others.shuffle(rng);
+
+            let (op_type, operand) = if i == squaring {
+                (OpType::Multiply, None)
+            } else if rng.gen_bool(0.5) {
+                (OpType::Multiply, Some(rng.gen_range(2..=19)))
+            } else {
+                (OpType::Add, Some(rng.gen_range(1..=8)))
+            };
 
             Monkey {
-                items,
-                operation,
+                items: vec![],
+                operation: Operation {
+                    op_type,
+                    left: None,
+                    right: operand,
+                },
                 test: Test {
-                    divisible_by,
-                    throw_true,
-                    throw_false,
+                    divisible_by: divisors[i],
+                    throw_true: others[0],
+                    throw_false: *others.get(1).unwrap_or(&others[0]),
                 },
                 inspected_count: 0,
             }
         })
-        .collect::<Vec<Monkey>>()
+        .collect();
+
+    let mut items: Vec<Vec<u64>> = vec![vec![]; count];
+    for _ in 0..size.max(1) {
+        let (monkey, item) = (rng.gen_range(0..count), rng.gen_range(50..=99));
+        if !overflows(&monkeys, monkey, item) {
+            items[monkey].push(item);
+        }
+    }
+    for (monkey, items) in monkeys.iter_mut().zip(items) {
+        monkey.items = items;
+    }
+
+    let blocks: Vec<String> = monkeys
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let items: Vec<String> = m.items.iter().map(|i| i.to_string()).collect();
+            let operand = |value: Option<u64>| value.map_or("old".to_owned(), |v| v.to_string());
+            let operation = match m.operation.op_type {
+                OpType::Add => "+",
+                OpType::Substract => "-",
+                OpType::Multiply => "*",
+                OpType::Divide => "/",
+            };
+
+            [
+                format!("Monkey {}:", i),
+                format!("  Starting items: {}", items.join(", ")),
+                format!(
+                    "  Operation: new = {} {} {}",
+                    operand(m.operation.left),
+                    operation,
+                    operand(m.operation.right)
+                ),
+                format!("  Test: divisible by {}", m.test.divisible_by),
+                format!("    If true: throw to monkey {}", m.test.throw_true),
+                format!("    If false: throw to monkey {}\n", m.test.throw_false),
+            ]
+            .join("\n")
+        })
+        .collect();
+
+    blocks.join("\n")
+}
+
+/// Whether the worry level of an item overflows during the rounds of part 1, when it is the
+/// only one the monkeys pass around
+fn overflows(monkeys: &[Monkey], monkey: usize, item: u64) -> bool {
+    let mut monkeys = monkeys.to_vec();
+    monkeys[monkey].items.push(item);
+
+    monkey_business_level(&mut monkeys, 20, true).is_err()
+}
+
+pub struct Day11 {
+    monkeys: Vec<Monkey>,
+    part1_rounds: u64,
+    part2_rounds: u64,
+    part1_relief: bool,
+    part2_relief: bool,
+}
+
+impl Default for Day11 {
+    fn default() -> Self {
+        Day11 {
+            monkeys: vec![],
+            part1_rounds: 20,
+            part2_rounds: 10000,
+            part1_relief: true,
+            part2_relief: false,
+        }
+    }
+}
+
+impl Solution for Day11 {
+    fn day(&self) -> u8 {
+        11
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.monkeys = parse_input(data)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        let mut monkeys = self.monkeys.clone();
+        Ok(monkey_business_level(&mut monkeys, self.part1_rounds, self.part1_relief)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        let mut monkeys = self.monkeys.clone();
+        Ok(monkey_business_level(&mut monkeys, self.part2_rounds, self.part2_relief)?.into())
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("part1_rounds", self.part1_rounds.to_string()),
+            ("part2_rounds", self.part2_rounds.to_string()),
+            ("part1_relief", self.part1_relief.to_string()),
+            ("part2_relief", self.part2_relief.to_string()),
+        ]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "part1_rounds" => self.part1_rounds = parse_param_in(DAY, name, value, 1..=MAX_ROUNDS)?,
+            "part2_rounds" => self.part2_rounds = parse_param_in(DAY, name, value, 1..=MAX_ROUNDS)?,
+            "part1_relief" => self.part1_relief = parse_param(DAY, name, value)?,
+            "part2_relief" => self.part2_relief = parse_param(DAY, name, value)?,
+            _ => return Err(unknown_param(DAY, name)),
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     struct TestCase {
         input: Vec<Monkey>,
@@ -149,35 +370,7 @@ mod tests {
     }
 
     fn get_data() -> String {
-        return String::from(
-            "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1",
-        );
+        String::from(include_str!("../data/examples/day11/example.txt"))
     }
 
     #[test]
@@ -241,7 +434,7 @@ Monkey 3:
             },
         ];
 
-        let result = parse_input(get_data());
+        let result = parse_input(&get_data()).unwrap();
 
         assert_eq!(result, expected);
     }
@@ -249,11 +442,11 @@ Monkey 3:
     #[test]
     fn it_should_calculate_monkey_business_level_20_rounds() {
         let mut test_case = TestCase {
-            input: parse_input(get_data()),
+            input: parse_input(&get_data()).unwrap(),
             expected: 10605,
         };
 
-        let result = monkey_business_level(&mut test_case.input, 20, true);
+        let result = monkey_business_level(&mut test_case.input, 20, true).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
@@ -261,11 +454,11 @@ Monkey 3:
     #[test]
     fn it_should_calculate_monkey_business_level_10000_rounds_no_relief() {
         let mut test_case = TestCase {
-            input: parse_input(get_data()),
+            input: parse_input(&get_data()).unwrap(),
             expected: 2713310158,
         };
 
-        let result = monkey_business_level(&mut test_case.input, 10000, false);
+        let result = monkey_business_level(&mut test_case.input, 10000, false).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
@@ -273,12 +466,156 @@ Monkey 3:
     #[test]
     fn it_should_calculate_monkey_business_level_20_rounds_no_relief() {
         let mut test_case = TestCase {
-            input: parse_input(get_data()),
+            input: parse_input(&get_data()).unwrap(),
             expected: 10197,
         };
 
-        let result = monkey_business_level(&mut test_case.input, 20, false);
+        let result = monkey_business_level(&mut test_case.input, 20, false).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
+
+    #[test]
+    fn it_should_report_invalid_monkeys() {
+        let operation = parse_input(&get_data().replace("old * 19", "old ^ 19")).unwrap_err();
+        let missing =
+            parse_input(&get_data().replace("    If false: throw to monkey 0\n", "")).unwrap_err();
+        let target =
+            parse_input(&get_data().replace("throw to monkey 3", "throw to monkey 4")).unwrap_err();
+
+        assert_eq!((operation.line, operation.column), (Some(3), Some(24)));
+        assert_eq!(missing.line, Some(8));
+        assert_eq!(target.line, Some(6));
+    }
+
+    #[test]
+    fn it_should_need_two_monkeys() {
+        let mut monkeys = parse_input(&get_data()).unwrap();
+
+        let one = monkey_business_level(&mut monkeys[..1], 20, true).unwrap_err();
+        let none = monkey_business_level(&mut [], 20, true).unwrap_err();
+
+        assert_eq!(
+            one.message,
+            "monkey business needs at least two monkeys, found 1"
+        );
+        assert!(none.message.ends_with("found 0"));
+    }
+
+    #[derive(Debug, Clone)]
+    struct MonkeyModel {
+        items: Vec<u64>,
+        /// Number to multiply or add the worry level with, `None` to square it
+        operand: Option<u64>,
+        multiply: bool,
+        divisor: u64,
+        targets: [usize; 2],
+    }
+
+    fn monkeys() -> impl Strategy<Value = Vec<MonkeyModel>> {
+        (2..=6usize).prop_flat_map(|count| {
+            let monkey = (
+                prop::collection::vec(1..100u64, 0..5),
+                prop::option::of(1..20u64),
+                any::<bool>(),
+                prop::sample::select(vec![2, 3, 5, 7, 11, 13, 17, 19, 23]),
+                [0..count - 1, 0..count - 1],
+            );
+
+            prop::collection::vec(monkey, count).prop_map(|monkeys| {
+                monkeys
+                    .into_iter()
+                    .enumerate()
+                    .map(
+                        |(i, (items, operand, multiply, divisor, targets))| MonkeyModel {
+                            items,
+                            operand,
+                            multiply: multiply || operand.is_none(),
+                            divisor,
+                            targets: targets.map(|t| if t >= i { t + 1 } else { t }),
+                        },
+                    )
+                    .collect()
+            })
+        })
+    }
+
+    fn to_input(monkeys: &[MonkeyModel]) -> String {
+        let blocks: Vec<String> = monkeys
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let items: Vec<String> = m.items.iter().map(|i| i.to_string()).collect();
+                let operand = m.operand.map_or("old".to_owned(), |o| o.to_string());
+                let operation = if m.multiply { "*" } else { "+" };
+                [
+                    format!("Monkey {}:", i),
+                    format!("  Starting items: {}", items.join(", ")),
+                    format!("  Operation: new = old {} {}", operation, operand),
+                    format!("  Test: divisible by {}", m.divisor),
+                    format!("    If true: throw to monkey {}", m.targets[0]),
+                    format!("    If false: throw to monkey {}", m.targets[1]),
+                ]
+                .join("\n")
+            })
+            .collect();
+
+        blocks.join("\n\n")
+    }
+
+    /// Monkey business after the given rounds, or `None` when a worry level no longer fits in
+    /// a `u64`
+    fn reference_business(monkeys: &[MonkeyModel], rounds: u64, with_relief: bool) -> Option<u64> {
+        let product: u128 = monkeys.iter().map(|m| u128::from(m.divisor)).product();
+        let mut items: Vec<Vec<u128>> = monkeys
+            .iter()
+            .map(|m| m.items.iter().map(|i| u128::from(*i)).collect())
+            .collect();
+        let mut inspected = vec![0u64; monkeys.len()];
+
+        for _ in 0..rounds {
+            for (i, m) in monkeys.iter().enumerate() {
+                for item in std::mem::take(&mut items[i]) {
+                    let operand = m.operand.map_or(item, u128::from);
+                    let item = if m.multiply {
+                        item * operand
+                    } else {
+                        item + operand
+                    };
+                    if item > u128::from(u64::MAX) {
+                        return None;
+                    }
+
+                    let item = if with_relief {
+                        item / 3
+                    } else {
+                        item % product
+                    };
+                    let target = m.targets[usize::from(item % u128::from(m.divisor) != 0)];
+                    items[target].push(item);
+                    inspected[i] += 1;
+                }
+            }
+        }
+
+        inspected.sort_unstable_by(|a, b| b.cmp(a));
+        Some(inspected[0] * inspected[1])
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_monkeys(monkeys in monkeys(), rounds in 1..200u64) {
+            let mut parsed = parse_input(&to_input(&monkeys)).unwrap();
+            let mut relieved = parsed.clone();
+
+            prop_assert_eq!(
+                monkey_business_level(&mut relieved, 20, true).ok(),
+                reference_business(&monkeys, 20, true)
+            );
+            prop_assert_eq!(
+                monkey_business_level(&mut parsed, rounds, false).ok(),
+                reference_business(&monkeys, rounds, false)
+            );
+        }
+    }
 }
@@ -1,101 +1,256 @@
-use std::collections::HashMap;
+use aoc::error::Result;
+use aoc::parse::{self, Line};
+use aoc::solution::{Answer, Solution};
+use rand::Rng;
+use std::io::BufRead;
 
-pub fn total_score_own_strat(data: String) -> i32 {
-    let plays: Vec<&str> = data.split('\n').filter(|p| !p.is_empty()).collect();
-    let mut score: i32 = 0;
+const DAY: u8 = 2;
 
-    for play in &plays {
-        let players: Vec<&str> = play.split(' ').collect();
-        let own_hand = players[1];
+/// Shape a player shows in a round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
 
-        score += get_hand_value(own_hand);
-        score += get_own_strategy_result(play);
+    /// Shape written as `A`, `B` or `C` in the first column of the guide
+    pub fn from_opponent(letter: &str) -> Option<Shape> {
+        match letter {
+            "A" => Some(Shape::Rock),
+            "B" => Some(Shape::Paper),
+            "C" => Some(Shape::Scissors),
+            _ => None,
+        }
     }
 
-    score
-}
+    /// Shape written as `X`, `Y` or `Z` in the second column of the guide
+    pub fn from_own(letter: &str) -> Option<Shape> {
+        match letter {
+            "X" => Some(Shape::Rock),
+            "Y" => Some(Shape::Paper),
+            "Z" => Some(Shape::Scissors),
+            _ => None,
+        }
+    }
 
-pub fn total_score_elf_strat(data: String) -> i32 {
-    let plays: Vec<&str> = data.split('\n').filter(|p| !p.is_empty()).collect();
-    let mut score: i32 = 0;
+    /// Shape this one defeats
+    pub fn beats(self) -> Shape {
+        match self {
+            Shape::Rock => Shape::Scissors,
+            Shape::Paper => Shape::Rock,
+            Shape::Scissors => Shape::Paper,
+        }
+    }
+
+    /// Shape defeating this one
+    pub fn loses_to(self) -> Shape {
+        match self {
+            Shape::Rock => Shape::Paper,
+            Shape::Paper => Shape::Scissors,
+            Shape::Scissors => Shape::Rock,
+        }
+    }
 
-    for play in &plays {
-        let players: Vec<&str> = play.split(' ').collect();
-        let opponent_hand = players[0];
-        let strat = players[1];
-        let own_hand = &get_own_hand(opponent_hand, strat);
+    /// Outcome of showing this shape against the `opponent` one
+    pub fn against(self, opponent: Shape) -> Outcome {
+        if self.beats() == opponent {
+            Outcome::Win
+        } else if self.loses_to() == opponent {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
 
-        score += get_hand_value(own_hand);
-        score += get_own_strategy_result(&format!("{} {}", opponent_hand, own_hand));
+    /// Score for showing the shape
+    pub fn score(self) -> i32 {
+        match self {
+            Shape::Rock => 1,
+            Shape::Paper => 2,
+            Shape::Scissors => 3,
+        }
     }
+}
 
-    score
+/// Outcome of a round for the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
 }
 
-fn get_own_hand(opponent_hand: &str, strat: &str) -> String {
-    match strat {
-        "X" => {
-            if opponent_hand == "A" {
-                "Z".to_owned()
-            } else if opponent_hand == "B" {
-                "X".to_owned()
-            } else {
-                "Y".to_owned()
-            }
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
+    /// Outcome written as `X`, `Y` or `Z` in the second column of the guide, as the elf meant it
+    pub fn from_letter(letter: &str) -> Option<Outcome> {
+        match letter {
+            "X" => Some(Outcome::Loss),
+            "Y" => Some(Outcome::Draw),
+            "Z" => Some(Outcome::Win),
+            _ => None,
         }
-        "Y" => {
-            if opponent_hand == "A" {
-                "X".to_owned()
-            } else if opponent_hand == "B" {
-                "Y".to_owned()
-            } else {
-                "Z".to_owned()
-            }
+    }
+
+    /// Shape to show against the `opponent` one to get this outcome
+    pub fn shape_against(self, opponent: Shape) -> Shape {
+        match self {
+            Outcome::Loss => opponent.beats(),
+            Outcome::Draw => opponent,
+            Outcome::Win => opponent.loses_to(),
         }
-        "Z" => {
-            if opponent_hand == "A" {
-                "Y".to_owned()
-            } else if opponent_hand == "B" {
-                "Z".to_owned()
-            } else {
-                "X".to_owned()
-            }
+    }
+
+    pub fn score(self) -> i32 {
+        match self {
+            Outcome::Loss => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
         }
-        _ => "".to_owned(),
     }
 }
 
-fn get_hand_value(hand: &str) -> i32 {
-    let hands = HashMap::from([("X", 1), ("Y", 2), ("Z", 3)]);
+/// Round of the strategy guide, with its second column read as a [`Shape`] to show or as an
+/// [`Outcome`] to get
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round<T> {
+    pub opponent: Shape,
+    pub own: T,
+}
+
+impl Round<Shape> {
+    /// Score when the second column is the shape to show
+    pub fn score(&self) -> i32 {
+        self.own.score() + self.own.against(self.opponent).score()
+    }
+}
+
+impl Round<Outcome> {
+    /// Score when the second column is the outcome to get, as the elf meant it
+    pub fn score(&self) -> i32 {
+        self.own.shape_against(self.opponent).score() + self.own.score()
+    }
+}
+
+/// Reads the letter of the second column, `None` when it is not one of the guide
+type Decoder<T> = fn(&str) -> Option<T>;
+
+pub fn total_score_own_strat(data: &str) -> Result<i32> {
+    total_score(
+        parse::lines(DAY, data).map(|line| get_round(line, Shape::from_own)),
+        Round::<Shape>::score,
+    )
+}
+
+/// Same as [`total_score_own_strat`], reading the input one line at a time
+pub fn total_score_own_strat_from_reader(reader: impl BufRead) -> Result<i32> {
+    total_score(
+        parse::read_lines(DAY, reader, |line| get_round(line, Shape::from_own)),
+        Round::<Shape>::score,
+    )
+}
+
+pub fn total_score_elf_strat(data: &str) -> Result<i32> {
+    total_score(
+        parse::lines(DAY, data).map(|line| get_round(line, Outcome::from_letter)),
+        Round::<Outcome>::score,
+    )
+}
+
+/// Same as [`total_score_elf_strat`], reading the input one line at a time
+pub fn total_score_elf_strat_from_reader(reader: impl BufRead) -> Result<i32> {
+    total_score(
+        parse::read_lines(DAY, reader, |line| get_round(line, Outcome::from_letter)),
+        Round::<Outcome>::score,
+    )
+}
 
-    match hands.get(hand) {
-        Some(score) => score.to_owned(),
-        None => 0,
+fn get_rounds<T>(data: &str, own: Decoder<T>) -> Result<Vec<Round<T>>> {
+    parse::lines(DAY, data)
+        .map(|line| get_round(line, own))
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+/// Round written on a line, its second column read by `own`, or `None` when the line is empty
+fn get_round<T>(line: Line, own: Decoder<T>) -> Result<Option<Round<T>>> {
+    if line.text.is_empty() {
+        return Ok(None);
     }
+
+    let [opponent, column] = line.split(
+        line.text,
+        ' ',
+        "expected the opponent and own hands separated by a space",
+    )?;
+
+    Ok(Some(Round {
+        opponent: Shape::from_opponent(opponent)
+            .ok_or_else(|| line.error_at(opponent, "expected `A`, `B` or `C`"))?,
+        own: own(column).ok_or_else(|| line.error_at(column, "expected `X`, `Y` or `Z`"))?,
+    }))
+}
+
+fn total_score<T>(
+    rounds: impl Iterator<Item = Result<Option<Round<T>>>>,
+    score: fn(&Round<T>) -> i32,
+) -> Result<i32> {
+    rounds
+        .filter_map(Result::transpose)
+        .map(|round| Ok(score(&round?)))
+        .sum()
+}
+
+/// Random puzzle input with `size` rounds
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.gen_range('A'..='C'),
+                rng.gen_range('X'..='Z')
+            )
+        })
+        .collect()
 }
 
-fn get_own_strategy_result(play: &str) -> i32 {
-    let play_results = HashMap::from([
-        ("A X", 3),
-        ("A Y", 6),
-        ("A Z", 0),
-        ("B X", 0),
-        ("B Y", 3),
-        ("B Z", 6),
-        ("C X", 6),
-        ("C Y", 0),
-        ("C Z", 3),
-    ]);
+#[derive(Default)]
+pub struct Day2 {
+    own_strat: Vec<Round<Shape>>,
+    elf_strat: Vec<Round<Outcome>>,
+}
+
+impl Solution for Day2 {
+    fn day(&self) -> u8 {
+        2
+    }
 
-    match play_results.get(play) {
-        Some(score) => score.to_owned(),
-        None => 0,
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.own_strat = get_rounds(data, Shape::from_own)?;
+        self.elf_strat = get_rounds(data, Outcome::from_letter)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        let score: i32 = self.own_strat.iter().map(Round::<Shape>::score).sum();
+        Ok(score.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        let score: i32 = self.elf_strat.iter().map(Round::<Outcome>::score).sum();
+        Ok(score.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     struct TestCase {
         input: String,
@@ -104,36 +259,105 @@ mod tests {
 
     #[test]
     fn it_should_return_total_score_own_strat() {
-        let contents = String::from(
-            "A Y
-B X
-C Z",
-        );
+        let contents = String::from(include_str!("../data/examples/day2/example.txt"));
         let test_case = TestCase {
             input: contents,
             expected: 15,
         };
 
-        let result = total_score_own_strat(test_case.input);
+        let result = total_score_own_strat(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
 
     #[test]
     fn it_should_return_total_score_elf_strat() {
-        let contents = String::from(
-            "A Y
-B X
-C Z
-A X",
-        );
+        let contents = String::from(include_str!("../data/examples/day2/four_rounds.txt"));
         let test_case = TestCase {
             input: contents,
             expected: 15,
         };
 
-        let result = total_score_elf_strat(test_case.input);
+        let result = total_score_elf_strat(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
+
+    #[test]
+    fn it_should_read_plays_from_a_reader() {
+        let contents = include_str!("../data/examples/day2/example.txt");
+
+        assert_eq!(
+            total_score_own_strat_from_reader(contents.as_bytes()),
+            Ok(15)
+        );
+        assert_eq!(
+            total_score_elf_strat_from_reader(contents.as_bytes()),
+            Ok(12)
+        );
+    }
+
+    #[test]
+    fn it_should_report_incomplete_plays() {
+        let result = total_score_own_strat("A Y\nBX");
+
+        assert_eq!(result.unwrap_err().line, Some(2));
+    }
+
+    #[test]
+    fn it_should_report_unknown_letters() {
+        let opponent = total_score_own_strat("A Y\nD X").unwrap_err();
+        let own = total_score_elf_strat("A Y\nB W").unwrap_err();
+
+        assert_eq!((opponent.line, opponent.column), (Some(2), Some(1)));
+        assert_eq!((own.line, own.column), (Some(2), Some(3)));
+    }
+
+    #[test]
+    fn it_should_read_the_second_column_both_ways() {
+        assert_eq!(Shape::from_opponent("A"), Some(Shape::Rock));
+        assert_eq!(Shape::from_own("Z"), Some(Shape::Scissors));
+        assert_eq!(Outcome::from_letter("Z"), Some(Outcome::Win));
+        assert_eq!(Shape::from_own("A"), None);
+    }
+
+    #[test]
+    fn it_should_relate_shapes_and_outcomes() {
+        for shape in Shape::ALL {
+            assert_eq!(shape.beats().loses_to(), shape);
+            assert_eq!(shape.against(shape.beats()), Outcome::Win);
+            assert_eq!(shape.against(shape), Outcome::Draw);
+            assert_eq!(shape.against(shape.loses_to()), Outcome::Loss);
+
+            for outcome in Outcome::ALL {
+                assert_eq!(outcome.shape_against(shape).against(shape), outcome);
+            }
+        }
+    }
+
+    /// Rounds as the opponent shape and the second column, both from 0 to 2
+    fn rounds() -> impl Strategy<Value = Vec<(u8, u8)>> {
+        prop::collection::vec((0..3u8, 0..3u8), 0..50)
+    }
+
+    /// Score of a round, where 0 stands for rock and for a loss
+    fn reference_score(shape: u8, outcome: u8) -> i32 {
+        i32::from(shape + 1 + 3 * outcome)
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_scores(rounds in rounds()) {
+            let data: Vec<String> = rounds
+                .iter()
+                .map(|(o, s)| format!("{} {}", (b'A' + o) as char, (b'X' + s) as char))
+                .collect();
+
+            let own: i32 = rounds.iter().map(|(o, s)| reference_score(*s, (s + 4 - o) % 3)).sum();
+            let elf: i32 = rounds.iter().map(|(o, s)| reference_score((o + s + 2) % 3, *s)).sum();
+
+            prop_assert_eq!(total_score_own_strat(&data.join("\n")), Ok(own));
+            prop_assert_eq!(total_score_elf_strat(&data.join("\n")), Ok(elf));
+        }
+    }
 }
@@ -1,14 +1,33 @@
+use aoc::error::{Error, Result};
+use aoc::parse;
+use aoc::solution::{Answer, Solution};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+const DAY: u8 = 3;
+
 #[derive(Clone, Debug)]
 pub struct Rucksack {
     pub items: Vec<Item>,
+    /// Number of the input line listing the items
+    pub line: usize,
+    text: String,
 }
 
 impl Rucksack {
-    pub fn new(items: String) -> Rucksack {
-        let item_names: Vec<&str> = items.split("").filter(|i| !i.is_empty()).collect();
-        let all_items: Vec<Item> = item_names.iter().map(|i| Item::new(i)).collect();
+    pub fn new(items: String, line: usize) -> Rucksack {
+        let all_items: Vec<Item> = items.chars().map(|i| Item::new(&i.to_string())).collect();
 
-        Rucksack { items: all_items }
+        Rucksack {
+            items: all_items,
+            line,
+            text: items,
+        }
+    }
+
+    /// Error pointing at the start of the line listing the items
+    fn error(&self, message: &str) -> Error {
+        Error::at(DAY, self.line, &self.text, 1, message)
     }
 
     pub fn get_misplaced_items(&self) -> Vec<Item> {
@@ -35,7 +54,7 @@ impl Rucksack {
             .map(|s| s.into())
             .collect();
 
-        pockets.get(0).unwrap().to_vec()
+        pockets.first().unwrap().to_vec()
     }
 
     pub fn get_right_pocket(&self) -> Vec<Item> {
@@ -100,16 +119,38 @@ fn get_item_prio(name: &str) -> i32 {
     }
 }
 
-pub fn sum_misplaced_prio(data: String) -> i32 {
-    // split lines and map to rucksacks
-    let rucksacks: Vec<Rucksack> = data
-        .lines()
-        .map(|line| Rucksack::new(line.to_owned()))
-        .collect();
+pub fn sum_misplaced_prio(data: &str) -> Result<i32> {
+    Ok(misplaced_prio(&get_rucksacks(data)?))
+}
+
+pub fn sum_elf_groups_prio(data: &str) -> Result<i32> {
+    elf_groups_prio(&get_rucksacks(data)?)
+}
+
+fn get_rucksacks(data: &str) -> Result<Vec<Rucksack>> {
+    parse::lines(DAY, data)
+        .map(|line| {
+            let items = line.text;
+            if let Some(column) = items.find(|c: char| !c.is_ascii_alphabetic()) {
+                return Err(line.error(column + 1, "items must be letters"));
+            }
+            if items.is_empty() || items.len() % 2 != 0 {
+                return Err(line.error(
+                    1,
+                    format!("expected an even number of items, found {}", items.len()),
+                ));
+            }
+
+            Ok(Rucksack::new(items.to_owned(), line.number))
+        })
+        .collect()
+}
+
+fn misplaced_prio(rucksacks: &[Rucksack]) -> i32 {
     let mut sum: i32 = 0;
 
     // loop over the rucksacks
-    for rucksack in &rucksacks {
+    for rucksack in rucksacks {
         // get misplaced items for rucksack
         let misplaced_items = rucksack.get_misplaced_items();
         // sum the prio for each item
@@ -125,32 +166,101 @@ pub fn sum_misplaced_prio(data: String) -> i32 {
     sum
 }
 
-pub fn sum_elf_groups_prio(data: String) -> i32 {
-    let rucksacks: Vec<Rucksack> = data
-        .lines()
-        .map(|line| Rucksack::new(line.to_owned()))
-        .collect();
-    let rucksacks_groups: Vec<Vec<Rucksack>> = rucksacks.chunks(3).map(|r| r.into()).collect();
-
-    rucksacks_groups
-        .iter()
-        .map(|g| {
-            ElfGroup::new(
-                g.get(0).unwrap().to_owned(),
-                g.get(1).unwrap().to_owned(),
-                g.get(2).unwrap().to_owned(),
-            )
+fn elf_groups_prio(rucksacks: &[Rucksack]) -> Result<i32> {
+    let mut sum: i32 = 0;
+
+    for g in rucksacks.chunks(3) {
+        let [elf1, elf2, elf3] = g else {
+            return Err(g[0].error("the group starting here has less than three elves"));
+        };
+
+        let badge = ElfGroup::new(elf1.to_owned(), elf2.to_owned(), elf3.to_owned())
             .get_item_badge()
-            .unwrap()
-            .prio
-        })
-        .reduce(|a, b| a + b)
-        .unwrap_or(0)
+            .ok_or_else(|| elf1.error("the group starting here has no badge"))?;
+        sum += badge.prio;
+    }
+
+    Ok(sum)
+}
+
+/// Random puzzle input with `size` rucksacks, rounded up to whole groups. Like the real ones,
+/// the two pockets of a rucksack share a single item and so do the rucksacks of a group.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut data = String::new();
+
+    for _ in 0..size.div_ceil(3).max(1) {
+        let badge = rng.gen_range(0..items.len());
+
+        for elf in 0..3 {
+            // Only the badge can be in the three rucksacks
+            let mut allowed: Vec<usize> = (0..items.len())
+                .filter(|i| i % 3 != elf && *i != badge)
+                .collect();
+            allowed.shuffle(rng);
+
+            let shared = if rng.gen_bool(0.1) {
+                badge
+            } else {
+                allowed.pop().unwrap()
+            };
+            let (left_items, right_items) = allowed.split_at(allowed.len() / 2);
+            let pocket_size = rng.gen_range(2..=left_items.len());
+            let mut left: Vec<usize> = vec![shared];
+            let mut right: Vec<usize> = vec![shared];
+
+            if shared != badge {
+                left.push(badge);
+            }
+            while left.len() < pocket_size {
+                left.push(*left_items.choose(rng).unwrap());
+            }
+            while right.len() < left.len() {
+                right.push(*right_items.choose(rng).unwrap());
+            }
+            left.shuffle(rng);
+            right.shuffle(rng);
+            if rng.gen_bool(0.5) {
+                std::mem::swap(&mut left, &mut right);
+            }
+
+            data.extend(left.iter().chain(&right).map(|i| items[*i]));
+            data.push('\n');
+        }
+    }
+
+    data
+}
+
+#[derive(Default)]
+pub struct Day3 {
+    rucksacks: Vec<Rucksack>,
+}
+
+impl Solution for Day3 {
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.rucksacks = get_rucksacks(data)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(misplaced_prio(&self.rucksacks).into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(elf_groups_prio(&self.rucksacks)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
 
     struct TestCase {
         input: String,
@@ -159,41 +269,107 @@ mod tests {
 
     #[test]
     fn it_should_return_total_misplaced_prio() {
-        let contents = String::from(
-            "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw",
-        );
+        let contents = String::from(include_str!("../data/examples/day3/example.txt"));
         let test_case = TestCase {
             input: contents,
             expected: 157,
         };
 
-        let result = sum_misplaced_prio(test_case.input);
+        let result = sum_misplaced_prio(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
 
     #[test]
     fn it_should_return_total_elf_group_prio() {
-        let contents = String::from(
-            "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw",
-        );
+        let contents = String::from(include_str!("../data/examples/day3/example.txt"));
         let test_case = TestCase {
             input: contents,
             expected: 70,
         };
 
-        let result = sum_elf_groups_prio(test_case.input);
+        let result = sum_elf_groups_prio(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
+
+    #[test]
+    fn it_should_report_invalid_rucksacks() {
+        let odd = sum_misplaced_prio("abcA\nabc").unwrap_err();
+        let not_letter = sum_misplaced_prio("ab1A").unwrap_err();
+        let incomplete_group = sum_elf_groups_prio("abcA\nabcB").unwrap_err();
+        let no_badge = sum_elf_groups_prio("aBbA\nabcB\nxyzD").unwrap_err();
+
+        assert_eq!(odd.line, Some(2));
+        assert_eq!(not_letter.column, Some(3));
+        assert_eq!(
+            (incomplete_group.line, incomplete_group.column),
+            (Some(1), Some(1))
+        );
+        assert_eq!(no_badge.line, Some(1));
+        assert!(no_badge.diagnostic().contains("aBbA"));
+    }
+
+    const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    /// Groups of three rucksacks sharing a single item. Each rucksack only holds items whose
+    /// index modulo 3 differs from its position in the group, besides the shared one.
+    fn groups() -> impl Strategy<Value = Vec<[String; 3]>> {
+        let group = (
+            0..ITEMS.len(),
+            prop::array::uniform3((
+                prop::collection::vec(0..ITEMS.len(), 1..20),
+                any::<prop::sample::Index>(),
+            )),
+        )
+            .prop_map(|(badge, elves)| {
+                let mut k = 0;
+                elves.map(|(items, position)| {
+                    let allowed = |i: &usize| i % 3 != k && *i != badge;
+                    let mut items: Vec<usize> = items.into_iter().filter(allowed).collect();
+                    items.insert(position.index(items.len() + 1), badge);
+                    if !items.len().is_multiple_of(2) {
+                        items.push((0..ITEMS.len()).find(allowed).unwrap());
+                    }
+                    k += 1;
+                    items.iter().map(|i| ITEMS[*i] as char).collect()
+                })
+            });
+
+        prop::collection::vec(group, 0..10)
+    }
+
+    fn reference_priority(items: HashSet<char>) -> i32 {
+        items
+            .iter()
+            .map(|c| ITEMS.iter().position(|i| *i as char == *c).unwrap() as i32 + 1)
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_priorities(groups in groups()) {
+            let rucksacks: Vec<&String> = groups.iter().flatten().collect();
+            let data = rucksacks.iter().map(|r| r.as_str()).collect::<Vec<_>>().join("\n");
+
+            let misplaced: i32 = rucksacks
+                .iter()
+                .map(|r| {
+                    let (left, right) = r.split_at(r.len() / 2);
+                    let right: HashSet<char> = right.chars().collect();
+                    reference_priority(left.chars().filter(|c| right.contains(c)).collect())
+                })
+                .sum();
+            let badges: i32 = groups
+                .iter()
+                .map(|[a, b, c]| {
+                    let shared = a.chars().filter(|i| b.contains(*i) && c.contains(*i));
+                    reference_priority(shared.collect())
+                })
+                .sum();
+
+            prop_assert_eq!(sum_misplaced_prio(&data), Ok(misplaced));
+            prop_assert_eq!(sum_elf_groups_prio(&data), Ok(badges));
+        }
+    }
 }
@@ -1,3 +1,10 @@
+use aoc::error::Result;
+use aoc::parse::{self, Line};
+use aoc::solution::{Answer, Solution};
+use rand::Rng;
+
+const DAY: u8 = 4;
+
 #[derive(Clone)]
 pub struct Section {
     pub start: i32,
@@ -24,33 +31,35 @@ pub struct Assignment {
 }
 
 impl Assignment {
-    pub fn new(line: String) -> Assignment {
-        let sections: Vec<Section> = line
-            .split(',')
-            .map(|s| {
-                let section: Vec<&str> = s.split('-').collect();
-
-                Section::new(
-                    section.first().unwrap().parse().unwrap(),
-                    section.get(1).unwrap().parse().unwrap(),
-                )
-            })
-            .collect();
-
-        Assignment {
-            pair: [
-                sections.first().unwrap().to_owned(),
-                sections.get(1).unwrap().to_owned(),
-            ],
-        }
+    /// Parses the assignment written in the given input line
+    pub fn parse(line: &Line) -> Result<Assignment> {
+        let pair: [&str; 2] = line.split(line.text, ',', "expected a pair of sections")?;
+        let [first, second] = pair.map(|s| {
+            let [start, end] = line.split(s, '-', "expected a section like `2-4`")?;
+            Ok(Section::new(line.parse(start)?, line.parse(end)?))
+        });
+
+        Ok(Assignment {
+            pair: [first?, second?],
+        })
     }
 }
 
-pub fn fully_covered_count(data: String) -> i32 {
-    let assignments: Vec<Assignment> = data
-        .lines()
-        .map(|line| Assignment::new(line.to_owned()))
-        .collect();
+pub fn fully_covered_count(data: &str) -> Result<i32> {
+    Ok(count_fully_covered(&get_assignments(data)?))
+}
+
+pub fn overlaping_count(data: &str) -> Result<i32> {
+    Ok(count_overlaping(&get_assignments(data)?))
+}
+
+fn get_assignments(data: &str) -> Result<Vec<Assignment>> {
+    parse::lines(DAY, data)
+        .map(|line| Assignment::parse(&line))
+        .collect()
+}
+
+fn count_fully_covered(assignments: &[Assignment]) -> i32 {
     let mut sum: i32 = 0;
 
     for a in assignments {
@@ -62,11 +71,7 @@ pub fn fully_covered_count(data: String) -> i32 {
     sum
 }
 
-pub fn overlaping_count(data: String) -> i32 {
-    let assignments: Vec<Assignment> = data
-        .lines()
-        .map(|line| Assignment::new(line.to_owned()))
-        .collect();
+fn count_overlaping(assignments: &[Assignment]) -> i32 {
     let mut sum: i32 = 0;
 
     for a in assignments {
@@ -78,9 +83,49 @@ pub fn overlaping_count(data: String) -> i32 {
     sum
 }
 
+/// Random puzzle input with `size` pairs of sections
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut section = || {
+        let start = rng.gen_range(1..=99);
+        (start, rng.gen_range(start..=99))
+    };
+
+    (0..size)
+        .map(|_| {
+            let ((a, b), (c, d)) = (section(), section());
+            format!("{}-{},{}-{}\n", a, b, c, d)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct Day4 {
+    assignments: Vec<Assignment>,
+}
+
+impl Solution for Day4 {
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.assignments = get_assignments(data)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(count_fully_covered(&self.assignments).into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(count_overlaping(&self.assignments).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     struct TestCase {
         input: String,
@@ -89,41 +134,63 @@ mod tests {
 
     #[test]
     fn it_should_return_fully_covered_count() {
-        let contents = String::from(
-            "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8",
-        );
+        let contents = String::from(include_str!("../data/examples/day4/example.txt"));
         let test_case = TestCase {
             input: contents,
             expected: 2,
         };
 
-        let result = fully_covered_count(test_case.input);
+        let result = fully_covered_count(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
 
     #[test]
     fn it_should_return_overlaping_assignments() {
-        let contents = String::from(
-            "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8",
-        );
+        let contents = String::from(include_str!("../data/examples/day4/example.txt"));
         let test_case = TestCase {
             input: contents,
             expected: 4,
         };
 
-        let result = overlaping_count(test_case.input);
+        let result = overlaping_count(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
+
+    #[test]
+    fn it_should_report_invalid_sections() {
+        let error = fully_covered_count("2-4,6-8\n2-3,4-x").unwrap_err();
+
+        assert_eq!((error.line, error.column), (Some(2), Some(7)));
+    }
+
+    /// Pairs of sections given by their first and last ids
+    fn pairs() -> impl Strategy<Value = Vec<[(i32, i32); 2]>> {
+        let section = (1..100i32, 1..100i32).prop_map(|(a, b)| (a.min(b), a.max(b)));
+        prop::collection::vec([section.clone(), section], 0..50)
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_counts(pairs in pairs()) {
+            let data = pairs
+                .iter()
+                .map(|[(a, b), (c, d)]| format!("{}-{},{}-{}", a, b, c, d))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let within = |(a, b): (i32, i32), (c, d): (i32, i32)| {
+                (a..=b).all(|x| (c..=d).contains(&x))
+            };
+            let covered = pairs.iter().filter(|[x, y]| within(*x, *y) || within(*y, *x)).count();
+            let overlaping = pairs
+                .iter()
+                .filter(|[(a, b), (c, d)]| (*a..=*b).any(|x| (*c..=*d).contains(&x)))
+                .count();
+
+            prop_assert_eq!(fully_covered_count(&data), Ok(covered as i32));
+            prop_assert_eq!(overlaping_count(&data), Ok(overlaping as i32));
+        }
+    }
 }
@@ -1,6 +1,11 @@
-use regex::Regex;
+use aoc::error::{Error, Result};
+use aoc::parse::{self, Line};
+use aoc::solution::{Answer, Solution};
+use rand::Rng;
 
-#[derive(Debug)]
+const DAY: u8 = 5;
+
+#[derive(Debug, Clone)]
 pub struct CrateStack {
     crates: Vec<String>,
 }
@@ -16,129 +21,252 @@ pub struct Instruction {
     num_crates: i32,
     from: i32,
     to: i32,
+    line: usize,
+    text: String,
 }
 
 impl Instruction {
-    fn new(num_crates: i32, from: i32, to: i32) -> Instruction {
+    fn new(num_crates: i32, from: i32, to: i32, line: &Line) -> Instruction {
         Instruction {
             num_crates,
             from,
             to,
+            line: line.number,
+            text: line.text.to_owned(),
         }
     }
+
+    /// Error pointing at the `index`th number of the instruction
+    fn error(&self, index: usize, message: String) -> Error {
+        let numbers = parse::integers(&self.text);
+        let part = numbers.get(index).copied().unwrap_or(&self.text);
+
+        Error::at_part(DAY, self.line, &self.text, part, message)
+    }
 }
 
-pub fn get_top_crates(data: String) -> String {
-    let mut stacks: Vec<CrateStack> = get_crate_stacks(data.to_owned());
-    let instructions = get_crane_instructions(data);
+pub fn get_top_crates(data: &str) -> Result<String> {
+    let mut stacks: Vec<CrateStack> = get_crate_stacks(data)?;
+    let instructions = get_crane_instructions(data)?;
 
-    for instr in instructions {
-        let from_stack = stacks.get_mut((instr.from as usize) - 1).unwrap();
-        let mut crates_to_move: Vec<String> = vec![];
+    move_crates(&mut stacks, &instructions)?;
+    top_crates(&stacks)
+}
 
-        for _ in 0..instr.num_crates {
-            let item = from_stack.crates.pop().unwrap();
-            crates_to_move.push(item.to_owned());
-        }
+pub fn get_top_crates_9001(data: &str) -> Result<String> {
+    let mut stacks: Vec<CrateStack> = get_crate_stacks(data)?;
+    let instructions = get_crane_instructions(data)?;
+
+    move_crates_9001(&mut stacks, &instructions)?;
+    top_crates(&stacks)
+}
+
+fn move_crates(stacks: &mut [CrateStack], instructions: &[Instruction]) -> Result<()> {
+    for instr in instructions {
+        let crates_to_move = take_crates(stacks, instr)?;
 
-        let to_stack = stacks.get_mut((instr.to as usize) - 1).unwrap();
+        let to_stack = &mut stacks[(instr.to as usize) - 1];
         for c in crates_to_move {
             to_stack.crates.push(c);
         }
     }
 
-    let mut top_crates = "".to_owned();
-    for stack in stacks {
-        top_crates += stack.crates.last().unwrap();
-    }
-    top_crates
+    Ok(())
 }
 
-pub fn get_top_crates_9001(data: String) -> String {
-    let mut stacks: Vec<CrateStack> = get_crate_stacks(data.to_owned());
-    let instructions = get_crane_instructions(data);
-
+fn move_crates_9001(stacks: &mut [CrateStack], instructions: &[Instruction]) -> Result<()> {
     for instr in instructions {
-        let from_stack = stacks.get_mut((instr.from as usize) - 1).unwrap();
-        let mut crates_to_move: Vec<String> = vec![];
+        let mut crates_to_move = take_crates(stacks, instr)?;
+        crates_to_move.reverse();
 
-        for _ in 0..instr.num_crates {
-            let item = from_stack.crates.pop().unwrap();
-            crates_to_move.insert(0, item.to_owned());
-        }
-
-        let to_stack = stacks.get_mut((instr.to as usize) - 1).unwrap();
+        let to_stack = &mut stacks[(instr.to as usize) - 1];
         for c in crates_to_move {
             to_stack.crates.push(c);
         }
     }
 
+    Ok(())
+}
+
+/// Pops the crates moved by an instruction, in the order they leave the stack
+fn take_crates(stacks: &mut [CrateStack], instr: &Instruction) -> Result<Vec<String>> {
+    for (index, stack_number) in [(1, instr.from), (2, instr.to)] {
+        if stack_number < 1 || stack_number as usize > stacks.len() {
+            return Err(instr.error(index, format!("there is no stack {}", stack_number)));
+        }
+    }
+
+    let from_stack = &mut stacks[(instr.from as usize) - 1];
+    if from_stack.crates.len() < instr.num_crates as usize {
+        return Err(instr.error(
+            0,
+            format!(
+                "cannot move {} crates from stack {}, it has {}",
+                instr.num_crates,
+                instr.from,
+                from_stack.crates.len()
+            ),
+        ));
+    }
+
+    let mut crates_to_move: Vec<String> = vec![];
+    for _ in 0..instr.num_crates {
+        crates_to_move.push(from_stack.crates.pop().unwrap());
+    }
+
+    Ok(crates_to_move)
+}
+
+fn top_crates(stacks: &[CrateStack]) -> Result<String> {
     let mut top_crates = "".to_owned();
-    for stack in stacks {
-        top_crates += stack.crates.last().unwrap();
+    for (i, stack) in stacks.iter().enumerate() {
+        match stack.crates.last() {
+            Some(c) => top_crates += c,
+            None => return Err(Error::new(DAY, format!("stack {} ends up empty", i + 1))),
+        }
     }
-    top_crates
+    Ok(top_crates)
 }
 
-fn get_crate_stacks(data: String) -> Vec<CrateStack> {
-    let parts: Vec<&str> = data.split("\n\n").collect();
-    let crate_stacks_data: Vec<&str> = parts.first().unwrap().lines().collect();
-    let stack_numbers: Vec<&str> = crate_stacks_data.last().unwrap().split("").collect();
-    let mut stacks: Vec<CrateStack> = stack_numbers
-        .iter()
-        .filter(|s| !s.trim().is_empty())
-        .map(|_| CrateStack::new())
-        .collect();
-    let re_letter = Regex::new(r"\w{1}").unwrap();
-
-    let mut i = 0;
-    while i < crate_stacks_data.len() - 1 {
-        let line: Vec<&str> = crate_stacks_data.get(i).unwrap().split("").collect();
-        let mut j = 0;
-
-        while j < line.len() {
-            let crate_letter = line.get(j).unwrap().to_owned();
-            if re_letter.is_match(crate_letter) {
-                let stack_number: i32 = stack_numbers.get(j).unwrap().parse().unwrap();
-                stacks
-                    .get_mut((stack_number as usize) - 1)
-                    .unwrap()
-                    .crates
-                    .insert(0, crate_letter.to_owned());
+/// Lines of the stacks drawing and of the crane instructions, which follow it after a blank line
+fn get_sections(data: &str) -> Result<(Vec<Line<'_>>, Vec<Line<'_>>)> {
+    let mut blocks = parse::blocks(DAY, data).into_iter();
+    let drawing = blocks.next().ok_or_else(|| {
+        Error::new(
+            DAY,
+            "expected the crate stacks drawing followed by a blank line",
+        )
+    })?;
+
+    Ok((drawing, blocks.flatten().collect()))
+}
+
+fn get_crate_stacks(data: &str) -> Result<Vec<CrateStack>> {
+    let (drawing, _) = get_sections(data)?;
+    let (numbers, rows) = drawing.split_last().unwrap();
+    let numbers = Line {
+        text: numbers.text.trim_end(),
+        ..*numbers
+    };
+    let mut stacks: Vec<CrateStack> = vec![];
+
+    for (i, number) in numbers.columns(3, 1).into_iter().enumerate() {
+        if numbers.parse::<usize>(number.trim())? != i + 1 {
+            return Err(numbers.error_at(number.trim(), format!("expected stack {}", i + 1)));
+        }
+        stacks.push(CrateStack::new());
+    }
+
+    for row in rows.iter().rev() {
+        for (i, cell) in row.columns(3, 1).into_iter().enumerate() {
+            if cell.trim().is_empty() {
+                continue;
+            }
+
+            let Some(stack) = stacks.get_mut(i) else {
+                return Err(row.error_at(cell, "crate is not above a stack number"));
+            };
+            match cell.strip_prefix('[').and_then(|c| c.strip_suffix(']')) {
+                Some(name)
+                    if name.chars().count() == 1 && name.chars().all(char::is_alphanumeric) =>
+                {
+                    stack.crates.push(name.to_owned())
+                }
+                _ => return Err(row.error_at(cell, "expected a crate like `[A]`")),
             }
-            j += 1;
         }
-        i += 1;
     }
 
-    stacks
+    Ok(stacks)
 }
 
-fn get_crane_instructions(data: String) -> Vec<Instruction> {
-    let parts: Vec<&str> = data.split("\n\n").collect();
-    let instructions_data = parts.last().unwrap();
-    let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
+fn get_crane_instructions(data: &str) -> Result<Vec<Instruction>> {
+    let (_, instructions) = get_sections(data)?;
 
-    let instructions: Vec<Instruction> = instructions_data
-        .lines()
-        .map(|l| {
-            let (mut num_crates, mut from, mut to): (i32, i32, i32) = (0, 0, 0);
-            for cap in re.captures_iter(l) {
-                num_crates = cap[1].parse().unwrap();
-                from = cap[2].parse().unwrap();
-                to = cap[3].parse().unwrap();
-            }
+    instructions
+        .iter()
+        .map(|line| {
+            let [num_crates, from, to] = line.scan("move {} from {} to {}")?;
+            Ok(Instruction::new(num_crates, from, to, line))
+        })
+        .collect()
+}
 
-            Instruction::new(num_crates, from, to)
+/// Random puzzle input with nine stacks and `size` moves, which never empty a stack
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut heights: Vec<usize> = (0..9).map(|_| rng.gen_range(1..=8)).collect();
+    let drawing_height = heights.iter().max().copied().unwrap_or(0);
+    let mut lines: Vec<String> = (0..drawing_height)
+        .rev()
+        .map(|row| {
+            let cells: Vec<String> = heights
+                .iter()
+                .map(|height| {
+                    if row < *height {
+                        format!("[{}]", rng.gen_range('A'..='Z'))
+                    } else {
+                        "   ".to_owned()
+                    }
+                })
+                .collect();
+            cells.join(" ")
         })
         .collect();
 
-    instructions
+    let numbers: Vec<String> = (1..=heights.len()).map(|n| format!(" {} ", n)).collect();
+    lines.push(numbers.join(" "));
+    lines.push(String::new());
+
+    while lines.len() < drawing_height + 2 + size {
+        let from = rng.gen_range(0..heights.len());
+        let to = rng.gen_range(0..heights.len());
+        if from == to || heights[from] < 2 {
+            continue;
+        }
+
+        let count = rng.gen_range(1..heights[from]);
+        heights[from] -= count;
+        heights[to] += count;
+        lines.push(format!("move {} from {} to {}", count, from + 1, to + 1));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[derive(Default)]
+pub struct Day5 {
+    stacks: Vec<CrateStack>,
+    instructions: Vec<Instruction>,
+}
+
+impl Solution for Day5 {
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.stacks = get_crate_stacks(data)?;
+        self.instructions = get_crane_instructions(data)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        let mut stacks = self.stacks.clone();
+        move_crates(&mut stacks, &self.instructions)?;
+        Ok(top_crates(&stacks)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        let mut stacks = self.stacks.clone();
+        move_crates_9001(&mut stacks, &self.instructions)?;
+        Ok(top_crates(&stacks)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     struct TestCase {
         input: String,
@@ -147,47 +275,135 @@ mod tests {
 
     #[test]
     fn it_should_return_top_crates() {
-        let contents = String::from(
-            "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2",
-        );
+        let contents = String::from(include_str!("../data/examples/day5/example.txt"));
         let test_case = TestCase {
             input: contents,
             expected: String::from("CMZ"),
         };
 
-        let result = get_top_crates(test_case.input);
+        let result = get_top_crates(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
 
     #[test]
     fn it_should_return_top_crates_9001() {
-        let contents = String::from(
-            "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2",
-        );
+        let contents = String::from(include_str!("../data/examples/day5/example.txt"));
         let test_case = TestCase {
             input: contents,
             expected: String::from("MCD"),
         };
 
-        let result = get_top_crates_9001(test_case.input);
+        let result = get_top_crates_9001(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
+
+    #[test]
+    fn it_should_report_invalid_instructions() {
+        let contents = String::from(
+            "[Z] [M]
+ 1   2 
+
+move 1 from 2 to 1
+move x from 1 to 2
+move 5 from 1 to 2",
+        );
+
+        let invalid = get_top_crates(&contents).unwrap_err();
+        let too_big = get_top_crates(&contents.replace("move x", "move 99999999999")).unwrap_err();
+        let too_many = get_top_crates(&contents.replace("move x", "move 1")).unwrap_err();
+
+        let no_stack =
+            get_top_crates(&contents.replace("move x from 1", "move 1 from 3")).unwrap_err();
+
+        assert_eq!((invalid.line, invalid.column), (Some(5), Some(1)));
+        assert_eq!((too_big.line, too_big.column), (Some(5), Some(6)));
+        assert_eq!((too_many.line, too_many.column), (Some(6), Some(6)));
+        assert_eq!((no_stack.line, no_stack.column), (Some(5), Some(13)));
+    }
+
+    /// Number of crates, source and target stack of a move
+    type Move = (usize, usize, usize);
+
+    /// Stacks of crates, from bottom to top, along with valid moves between them
+    fn crane_input() -> impl Strategy<Value = (Vec<Vec<char>>, Vec<Move>)> {
+        let crates = prop::collection::vec(prop::char::range('A', 'Z'), 0..8);
+        let moves =
+            prop::collection::vec((any::<prop::sample::Index>(), 0..9usize, 0..9usize), 0..30);
+
+        (prop::collection::vec(crates, 1..=9), moves).prop_map(|(stacks, moves)| {
+            let mut heights: Vec<usize> = stacks.iter().map(|s| s.len()).collect();
+            let moves = moves
+                .into_iter()
+                .filter_map(|(count, from, to)| {
+                    let (from, to) = (from % heights.len(), to % heights.len());
+                    if heights[from] == 0 {
+                        return None;
+                    }
+
+                    let count = count.index(heights[from]) + 1;
+                    heights[from] -= count;
+                    heights[to] += count;
+                    Some((count, from + 1, to + 1))
+                })
+                .collect();
+            (stacks, moves)
+        })
+    }
+
+    fn to_input(stacks: &[Vec<char>], moves: &[Move]) -> String {
+        let height = stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+        let mut lines: Vec<String> = (0..height)
+            .rev()
+            .map(|row| {
+                let cells: Vec<String> = stacks
+                    .iter()
+                    .map(|s| s.get(row).map_or("   ".to_owned(), |c| format!("[{}]", c)))
+                    .collect();
+                cells.join(" ")
+            })
+            .collect();
+
+        let numbers: Vec<String> = (1..=stacks.len()).map(|n| format!(" {} ", n)).collect();
+        lines.push(numbers.join(" "));
+        lines.push(String::new());
+        for (count, from, to) in moves {
+            lines.push(format!("move {} from {} to {}", count, from, to));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Top crates after moving them, or `None` when a stack ends up empty
+    fn reference_top_crates(
+        stacks: &[Vec<char>],
+        moves: &[Move],
+        keep_order: bool,
+    ) -> Option<String> {
+        let mut stacks = stacks.to_vec();
+        for (count, from, to) in moves {
+            let from_stack = &mut stacks[from - 1];
+            let mut moved = from_stack.split_off(from_stack.len() - count);
+            if !keep_order {
+                moved.reverse();
+            }
+            stacks[to - 1].extend(moved);
+        }
+
+        stacks.iter().map(|s| s.last()).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_crane((stacks, moves) in crane_input()) {
+            let data = to_input(&stacks, &moves);
+
+            let crane_9000 = reference_top_crates(&stacks, &moves, false);
+            let crane_9001 = reference_top_crates(&stacks, &moves, true);
+
+            prop_assert_eq!(get_top_crates(&data).ok(), crane_9000);
+            prop_assert_eq!(get_top_crates_9001(&data).ok(), crane_9001);
+        }
+    }
 }
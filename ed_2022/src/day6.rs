@@ -1,80 +1,154 @@
-struct Packet {
-    seq: String,
+use aoc::error::{Error, Result};
+use aoc::parse;
+use aoc::solution::{Answer, Solution};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+const DAY: u8 = 6;
+
+pub fn get_start_packet_marker(data: &str) -> Result<i32> {
+    find_marker(get_signal(data)?.bytes().map(Ok), 4)
+}
+
+/// Same as [`get_start_packet_marker`], reading the signal only up to the marker
+pub fn get_start_packet_marker_from_reader(reader: impl BufRead) -> Result<i32> {
+    find_marker(read_signal(reader), 4)
+}
+
+pub fn get_start_message_marker(data: &str) -> Result<i32> {
+    find_marker(get_signal(data)?.bytes().map(Ok), 14)
 }
 
-impl Packet {
-    fn new(seq: &str) -> Packet {
-        Packet {
-            seq: String::from(seq),
+/// Same as [`get_start_message_marker`], reading the signal only up to the marker
+pub fn get_start_message_marker_from_reader(reader: impl BufRead) -> Result<i32> {
+    find_marker(read_signal(reader), 14)
+}
+
+fn get_signal(data: &str) -> Result<&str> {
+    let signal = data.trim_end();
+
+    for line in parse::lines(DAY, signal) {
+        if line.number > 1 {
+            return Err(line.error(1, "the signal must be a single line"));
+        }
+        if let Some(column) = line.text.find(|c: char| !c.is_ascii_lowercase()) {
+            return Err(line.error(column + 1, "the signal must only have lowercase letters"));
         }
     }
 
-    fn is_start(&self) -> bool {
-        self.has_n_unique(4)
-    }
+    Ok(signal)
+}
 
-    fn is_message(&self) -> bool {
-        self.has_n_unique(14)
-    }
+/// Characters of the signal read one at a time, checked like [`get_signal`] does. Whitespace
+/// is only allowed at the end of the signal.
+fn read_signal(reader: impl BufRead) -> impl Iterator<Item = Result<u8>> {
+    let (mut line, mut column, mut blank) = (1, 0, 0);
 
-    fn has_n_unique(&self, n: usize) -> bool {
-        let mut packet_chars: Vec<char> = vec![];
-        let mut result = false;
+    reader.bytes().filter_map(move |byte| {
+        let byte = match byte {
+            Ok(byte) => byte,
+            Err(err) => return Some(Err(parse::read_error(DAY, line, err))),
+        };
+        column += 1;
 
-        for char in self.seq.chars() {
-            if packet_chars.contains(&char) {
-                break;
+        let error = |column, message| Error {
+            line: Some(line),
+            column: Some(column),
+            ..Error::new(DAY, message)
+        };
+        match byte {
+            b'\n' => {
+                (line, column, blank) = (line + 1, 0, 0);
+                None
             }
-            packet_chars.push(char)
-        }
-
-        if packet_chars.len() == n {
-            result = true;
+            _ if byte.is_ascii_whitespace() => {
+                blank += 1;
+                None
+            }
+            _ if line > 1 => Some(Err(error(1, "the signal must be a single line"))),
+            _ if blank > 0 || !byte.is_ascii_lowercase() => Some(Err(error(
+                column - blank,
+                "the signal must only have lowercase letters",
+            ))),
+            _ => Some(Ok(byte)),
         }
-
-        result
-    }
+    })
 }
 
-pub fn get_start_packet_marker(data: String) -> i32 {
-    let mut marker: usize = 0;
+/// Number of characters read until the last `size` ones are all different
+fn find_marker(signal: impl Iterator<Item = Result<u8>>, size: usize) -> Result<i32> {
+    let mut window: VecDeque<u8> = VecDeque::with_capacity(size);
 
-    loop {
-        if marker == (data.len() - 3) {
-            break;
+    for (i, c) in signal.enumerate() {
+        if window.len() == size {
+            window.pop_front();
         }
+        window.push_back(c?);
 
-        if Packet::new(&data[marker..marker + 4]).is_start() {
-            break;
+        let all_different = window
+            .iter()
+            .enumerate()
+            .all(|(j, c)| !window.range(j + 1..).any(|d| d == c));
+        if window.len() == size && all_different {
+            return Ok((i + 1) as i32);
         }
+    }
+
+    Err(Error::new(
+        DAY,
+        format!("no marker of {} different characters found", size),
+    ))
+}
 
-        marker += 1;
+/// Random puzzle input with a signal of `size` characters, at least 30. Its start only uses
+/// three letters so that the markers are not right at the beginning.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut letters: Vec<char> = ('a'..='z').collect();
+    letters.shuffle(rng);
+
+    let size = size.max(30);
+    let mut signal: String = (0..rng.gen_range(0..=size - 14))
+        .map(|_| *letters[..3].choose(rng).unwrap())
+        .collect();
+    signal.extend(&letters[..14]);
+    while signal.len() < size {
+        signal.push(rng.gen_range('a'..='z'));
     }
 
-    (marker + 4) as i32
+    signal + "\n"
 }
 
-pub fn get_start_message_marker(data: String) -> i32 {
-    let mut marker: usize = 0;
+#[derive(Default)]
+pub struct Day6 {
+    signal: String,
+}
 
-    loop {
-        if marker == (data.len() - 13) {
-            break;
-        }
+impl Solution for Day6 {
+    fn day(&self) -> u8 {
+        6
+    }
 
-        if Packet::new(&data[marker..marker + 14]).is_message() {
-            break;
-        }
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.signal = get_signal(data)?.to_owned();
+        Ok(())
+    }
 
-        marker += 1;
+    fn part1(&self) -> Result<Answer> {
+        Ok(find_marker(self.signal.bytes().map(Ok), 4)?.into())
     }
 
-    (marker + 14) as i32
+    fn part2(&self) -> Result<Answer> {
+        Ok(find_marker(self.signal.bytes().map(Ok), 14)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
 
     struct TestCase {
         input: String,
@@ -85,25 +159,25 @@ mod tests {
     fn it_should_get_start_packet_marker() {
         let test_cases: [TestCase; 4] = [
             TestCase {
-                input: String::from("mjqjpqmgbljsphdztnvjfqwrcgsmlb"),
+                input: String::from(include_str!("../data/examples/day6/example1.txt")),
                 expected: 7,
             },
             TestCase {
-                input: String::from("bvwbjplbgvbhsrlpgdmjqwftvncz"),
+                input: String::from(include_str!("../data/examples/day6/example2.txt")),
                 expected: 5,
             },
             TestCase {
-                input: String::from("nppdvjthqldpwncqszvftbrmjlhg"),
+                input: String::from(include_str!("../data/examples/day6/example3.txt")),
                 expected: 6,
             },
             TestCase {
-                input: String::from("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"),
+                input: String::from(include_str!("../data/examples/day6/example4.txt")),
                 expected: 10,
             },
         ];
 
         for test_case in test_cases {
-            let result = get_start_packet_marker(test_case.input);
+            let result = get_start_packet_marker(&test_case.input).unwrap();
 
             assert_eq!(result, test_case.expected);
         }
@@ -113,27 +187,75 @@ mod tests {
     fn it_should_get_start_message_marker() {
         let test_cases: [TestCase; 4] = [
             TestCase {
-                input: String::from("mjqjpqmgbljsphdztnvjfqwrcgsmlb"),
+                input: String::from(include_str!("../data/examples/day6/example1.txt")),
                 expected: 19,
             },
             TestCase {
-                input: String::from("bvwbjplbgvbhsrlpgdmjqwftvncz"),
+                input: String::from(include_str!("../data/examples/day6/example2.txt")),
                 expected: 23,
             },
             TestCase {
-                input: String::from("nppdvjthqldpwncqszvftbrmjlhg"),
+                input: String::from(include_str!("../data/examples/day6/example3.txt")),
                 expected: 23,
             },
             TestCase {
-                input: String::from("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"),
+                input: String::from(include_str!("../data/examples/day6/example4.txt")),
                 expected: 29,
             },
         ];
 
         for test_case in test_cases {
-            let result = get_start_message_marker(test_case.input);
+            let result = get_start_message_marker(&test_case.input).unwrap();
 
             assert_eq!(result, test_case.expected);
         }
     }
+
+    #[test]
+    fn it_should_read_signals_from_a_reader() {
+        let contents = include_str!("../data/examples/day6/example1.txt");
+        let invalid = get_start_packet_marker_from_reader("mjq pqmgb".as_bytes()).unwrap_err();
+        let second_line = get_start_packet_marker_from_reader("mjq\npq \n".as_bytes()).unwrap_err();
+
+        assert_eq!(
+            get_start_packet_marker_from_reader(contents.as_bytes()),
+            Ok(7)
+        );
+        assert_eq!(
+            get_start_message_marker_from_reader(contents.as_bytes()),
+            Ok(19)
+        );
+        assert_eq!((invalid.line, invalid.column), (Some(1), Some(4)));
+        assert_eq!((second_line.line, second_line.column), (Some(2), Some(1)));
+    }
+
+    #[test]
+    fn it_should_report_signals_without_marker() {
+        let invalid = get_start_packet_marker("mjqJpqmgb").unwrap_err();
+        let no_marker = get_start_packet_marker("aabbaab").unwrap_err();
+
+        assert_eq!(invalid.column, Some(4));
+        assert_eq!(no_marker.line, None);
+    }
+
+    /// Position right after the first `size` different characters, looking at every window
+    fn reference_marker(signal: &str, size: usize) -> Option<i32> {
+        (size..=signal.len())
+            .find(|end| {
+                signal[end - size..*end]
+                    .chars()
+                    .collect::<HashSet<char>>()
+                    .len()
+                    == size
+            })
+            .map(|end| end as i32)
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_markers(signal in "[a-p]{0,200}") {
+            prop_assert_eq!(get_start_packet_marker(&signal).ok(), reference_marker(&signal, 4));
+            prop_assert_eq!(get_start_message_marker(&signal).ok(), reference_marker(&signal, 14));
+        }
+    }
 }
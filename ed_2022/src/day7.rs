@@ -1,56 +1,90 @@
-use std::collections::HashMap;
+use aoc::error::{Error, Result};
+use aoc::parse;
+use aoc::solution::{parse_param, unknown_param, Answer, Solution};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
 
-pub fn total_dirs_size(data: String, limit: i64) -> i64 {
-    let dirs_sizes = get_dir_sizes(data);
+const DAY: u8 = 7;
 
-    Vec::from_iter(dirs_sizes.values().map(|s| s.to_owned()))
-        .into_iter()
-        .filter(|s| s <= &limit)
-        .sum()
+pub fn total_dirs_size(data: &str, limit: i64) -> Result<i64> {
+    Ok(sum_dirs_upto(&get_dir_sizes(data)?, limit))
 }
 
-pub fn dir_size_to_free(data: String, disk_space: i64, unused: i64) -> i64 {
-    let dirs_sizes = get_dir_sizes(data);
-    let available_space = disk_space - dirs_sizes.get("/").unwrap();
+pub fn dir_size_to_free(data: &str, disk_space: i64, unused: i64) -> Result<i64> {
+    smallest_dir_to_free(&get_dir_sizes(data)?, disk_space, unused)
+}
+
+fn sum_dirs_upto(dirs_sizes: &HashMap<String, i64>, limit: i64) -> i64 {
+    dirs_sizes.values().filter(|s| *s <= &limit).sum()
+}
 
-    Vec::from_iter(dirs_sizes.values().map(|s| s.to_owned()))
-        .into_iter()
-        .filter(|s| s + available_space >= unused)
+fn smallest_dir_to_free(
+    dirs_sizes: &HashMap<String, i64>,
+    disk_space: i64,
+    unused: i64,
+) -> Result<i64> {
+    let root_size = dirs_sizes
+        .get("/")
+        .ok_or_else(|| Error::new(DAY, "the terminal output never enters `/`"))?;
+    let available_space = disk_space
+        .checked_sub(*root_size)
+        .ok_or_else(|| Error::new(DAY, "the available space overflows"))?;
+
+    dirs_sizes
+        .values()
+        .filter(|s| s.saturating_add(available_space) >= unused)
         .min()
-        .unwrap()
+        .map(|s| s.to_owned())
+        .ok_or_else(|| {
+            Error::new(
+                DAY,
+                format!("no directory frees enough space to have {} unused", unused),
+            )
+        })
 }
 
-fn get_dir_sizes(data: String) -> HashMap<String, i64> {
-    let lines: Vec<&str> = data.lines().collect();
+fn get_dir_sizes(data: &str) -> Result<HashMap<String, i64>> {
     let mut dirs_sizes: HashMap<String, i64> = HashMap::new();
     let mut curr_dir: Vec<String> = vec![];
 
-    for line in lines {
-        let line_parts: Vec<&str> = line.split(' ').collect();
+    for line in parse::lines(DAY, data) {
+        let line_parts: Vec<&str> = line.text.split(' ').collect();
 
-        if line.starts_with('$') {
-            if line_parts.get(1).unwrap() == &"cd" {
-                if line_parts.get(2).unwrap() == &".." {
+        if line.text.starts_with('$') {
+            match line_parts.get(1..) {
+                Some(["cd", ".."]) => {
                     curr_dir.pop();
-                } else if line_parts.get(2).unwrap() != &"/" {
-                    curr_dir.push(format!("{}/", line_parts.get(2).unwrap()));
-                } else {
-                    curr_dir.push(line_parts.get(2).unwrap().to_string());
-                    dirs_sizes.insert(curr_dir.join(""), 0);
                 }
+                Some(["cd", "/"]) => {
+                    curr_dir = vec!["/".to_owned()];
+                    dirs_sizes.entry(curr_dir.join("")).or_insert(0);
+                }
+                Some(["cd", dir]) => curr_dir.push(format!("{}/", dir)),
+                Some(["ls"]) => {}
+                _ => return Err(line.error(1, "unknown command")),
             }
-        } else if line.starts_with("dir") {
-            dirs_sizes.insert(
-                format!("{}{}/", curr_dir.join(""), line_parts.get(1).unwrap()),
-                0,
-            );
+        } else if line.text.starts_with("dir") {
+            let Some(dir) = line_parts.get(1) else {
+                return Err(line.error(1, "expected `dir <name>`"));
+            };
+            dirs_sizes
+                .entry(format!("{}{}/", curr_dir.join(""), dir))
+                .or_insert(0);
         } else {
+            let size: i64 = line.parse(line_parts[0])?;
+            if size < 0 {
+                return Err(line.error_at(line_parts[0], "expected a non-negative file size"));
+            }
             let mut index = 0;
             let dir_slice = curr_dir.as_slice();
 
             while index < curr_dir.len() {
-                if let Some(sum) = dirs_sizes.get_mut(&dir_slice[0..index + 1].join("")) {
-                    *sum += line_parts.first().unwrap().parse::<i64>().unwrap();
+                let dir = dir_slice[0..index + 1].join("");
+                if let Some(sum) = dirs_sizes.get_mut(&dir) {
+                    *sum = sum.checked_add(size).ok_or_else(|| {
+                        line.error_at(line_parts[0], format!("the size of `{}` overflows", dir))
+                    })?;
                 }
 
                 index += 1;
@@ -58,12 +92,137 @@ fn get_dir_sizes(data: String) -> HashMap<String, i64> {
         }
     }
 
-    dirs_sizes
+    Ok(dirs_sizes)
+}
+
+/// Random puzzle input exploring `size` directories, at least one. Some of the filesystems are a
+/// single chain of nested directories.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let nested = rng.gen_bool(0.1);
+    let mut children: Vec<Vec<usize>> = vec![vec![]];
+    let mut names: Vec<String> = vec!["/".to_owned()];
+
+    for dir in 1..size.max(1) {
+        let parent = if nested {
+            dir - 1
+        } else {
+            rng.gen_range(0..dir)
+        };
+        let mut name = random_name(rng);
+        while children[parent].iter().any(|c| names[*c] == name) {
+            name.push(rng.gen_range('a'..='z'));
+        }
+
+        children[parent].push(dir);
+        children.push(vec![]);
+        names.push(name);
+    }
+
+    let mut lines = vec!["$ cd /".to_owned()];
+    write_listing(rng, &children[0], &names, &mut lines);
+
+    // Directories being explored, along with the next of their children to enter
+    let mut path: Vec<(usize, usize)> = vec![(0, 0)];
+    while let Some(&(dir, next)) = path.last() {
+        let Some(&child) = children[dir].get(next) else {
+            path.pop();
+            if !path.is_empty() {
+                lines.push("$ cd ..".to_owned());
+            }
+            continue;
+        };
+
+        path.last_mut().unwrap().1 += 1;
+        lines.push(format!("$ cd {}", names[child]));
+        write_listing(rng, &children[child], &names, &mut lines);
+        path.push((child, 0));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Appends an `ls` listing the given directories along with random files
+fn write_listing(rng: &mut impl Rng, dirs: &[usize], names: &[String], lines: &mut Vec<String>) {
+    let mut entries: Vec<String> = dirs.iter().map(|d| format!("dir {}", names[*d])).collect();
+    let mut files: HashSet<String> = HashSet::new();
+
+    for _ in 0..rng.gen_range(0..=4) {
+        let file = format!("{}.{}", random_name(rng), random_name(rng));
+        if files.insert(file.clone()) {
+            entries.push(format!("{} {}", rng.gen_range(1..=300_000), file));
+        }
+    }
+    entries.shuffle(rng);
+
+    lines.push("$ ls".to_owned());
+    lines.extend(entries);
+}
+
+fn random_name(rng: &mut impl Rng) -> String {
+    (0..rng.gen_range(1..=8))
+        .map(|_| rng.gen_range('a'..='z'))
+        .collect()
+}
+
+pub struct Day7 {
+    dirs_sizes: HashMap<String, i64>,
+    limit: i64,
+    disk_space: i64,
+    unused: i64,
+}
+
+impl Default for Day7 {
+    fn default() -> Self {
+        Day7 {
+            dirs_sizes: HashMap::new(),
+            limit: 100000,
+            disk_space: 70000000,
+            unused: 30000000,
+        }
+    }
+}
+
+impl Solution for Day7 {
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.dirs_sizes = get_dir_sizes(data)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(sum_dirs_upto(&self.dirs_sizes, self.limit).into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(smallest_dir_to_free(&self.dirs_sizes, self.disk_space, self.unused)?.into())
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("limit", self.limit.to_string()),
+            ("disk_space", self.disk_space.to_string()),
+            ("unused", self.unused.to_string()),
+        ]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "limit" => self.limit = parse_param(DAY, name, value)?,
+            "disk_space" => self.disk_space = parse_param(DAY, name, value)?,
+            "unused" => self.unused = parse_param(DAY, name, value)?,
+            _ => return Err(unknown_param(DAY, name)),
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     struct TestCase {
         input: String,
@@ -73,35 +232,11 @@ mod tests {
     #[test]
     fn it_should_get_total_dirs_size_upto_100000() {
         let test_case = TestCase {
-            input: String::from(
-                "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k",
-            ),
+            input: String::from(include_str!("../data/examples/day7/example.txt")),
             expected: 95437,
         };
 
-        let result = total_dirs_size(test_case.input, 100000);
+        let result = total_dirs_size(&test_case.input, 100000).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
@@ -109,36 +244,105 @@ $ ls
     #[test]
     fn it_should_get_dir_space_to_free() {
         let test_case = TestCase {
-            input: String::from(
-                "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k",
-            ),
+            input: String::from(include_str!("../data/examples/day7/example.txt")),
             expected: 24933642,
         };
 
-        let result = dir_size_to_free(test_case.input, 70000000, 30000000);
+        let result = dir_size_to_free(&test_case.input, 70000000, 30000000).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
+
+    #[test]
+    fn it_should_report_invalid_terminal_output() {
+        let unknown = total_dirs_size("$ cd /\n$ rm -rf a", 100).unwrap_err();
+        let bad_size = total_dirs_size("$ cd /\n$ ls\n12k a.txt", 100).unwrap_err();
+
+        assert_eq!(unknown.line, Some(2));
+        assert_eq!((bad_size.line, bad_size.column), (Some(3), Some(1)));
+    }
+
+    #[test]
+    fn it_should_report_negative_and_overflowing_sizes() {
+        let negative = total_dirs_size("$ cd /\n$ ls\n-5 a", 100).unwrap_err();
+        let overflow = "$ cd /\n$ ls\n9223372036854775807 a\n9223372036854775807 b";
+        let overflow = total_dirs_size(overflow, 100).unwrap_err();
+        let space = dir_size_to_free("$ cd /\n$ ls\n5 a", i64::MIN, 0).unwrap_err();
+
+        assert_eq!(negative.message, "expected a non-negative file size");
+        assert_eq!((overflow.line, overflow.column), (Some(4), Some(1)));
+        assert_eq!(overflow.message, "the size of `/` overflows");
+        assert_eq!(space.message, "the available space overflows");
+    }
+
+    #[derive(Debug, Clone)]
+    struct Dir {
+        files: Vec<i64>,
+        dirs: Vec<Dir>,
+    }
+
+    fn dir() -> impl Strategy<Value = Dir> {
+        let files = prop::collection::vec(1..100_000i64, 0..4);
+        let leaf = files.clone().prop_map(|files| Dir {
+            files,
+            dirs: vec![],
+        });
+
+        leaf.prop_recursive(5, 40, 4, move |dir| {
+            (files.clone(), prop::collection::vec(dir, 0..4))
+                .prop_map(|(files, dirs)| Dir { files, dirs })
+        })
+    }
+
+    /// Terminal output listing the contents of `dir`, then going through its directories
+    fn write_dir(dir: &Dir, lines: &mut Vec<String>) {
+        lines.push("$ ls".to_owned());
+        for i in 0..dir.dirs.len() {
+            lines.push(format!("dir d{}", i));
+        }
+        for (i, size) in dir.files.iter().enumerate() {
+            lines.push(format!("{} f{}.txt", size, i));
+        }
+        for (i, sub_dir) in dir.dirs.iter().enumerate() {
+            lines.push(format!("$ cd d{}", i));
+            write_dir(sub_dir, lines);
+            lines.push("$ cd ..".to_owned());
+        }
+    }
+
+    /// Size of `dir`, pushing the size of every directory inside it to `sizes`
+    fn reference_sizes(dir: &Dir, sizes: &mut Vec<i64>) -> i64 {
+        let size = dir.files.iter().sum::<i64>()
+            + dir
+                .dirs
+                .iter()
+                .map(|d| reference_sizes(d, sizes))
+                .sum::<i64>();
+        sizes.push(size);
+        size
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_sizes(root in dir()) {
+            let mut lines = vec!["$ cd /".to_owned()];
+            write_dir(&root, &mut lines);
+            let data = lines.join("\n");
+
+            let mut sizes = vec![];
+            let root_size = reference_sizes(&root, &mut sizes);
+            let (disk_space, unused) = (2_000_000, 1_000_000);
+            let to_free = sizes
+                .iter()
+                .filter(|s| *s + disk_space - root_size >= unused)
+                .min()
+                .copied();
+
+            prop_assert_eq!(
+                total_dirs_size(&data, 100_000),
+                Ok(sizes.iter().filter(|s| **s <= 100_000).sum())
+            );
+            prop_assert_eq!(dir_size_to_free(&data, disk_space, unused).ok(), to_free);
+        }
+    }
 }
@@ -1,4 +1,9 @@
-use std::collections::HashMap;
+use aoc::error::Result;
+use aoc::grid::{Direction, Grid};
+use aoc::solution::{Answer, Solution};
+use rand::Rng;
+
+const DAY: u8 = 8;
 
 #[derive(Debug, Clone)]
 struct Tree {
@@ -9,24 +14,29 @@ struct Tree {
     right: i64,
 }
 
-pub fn trees_visible(data: String) -> i64 {
-    get_trees_map(data)
-        .values()
-        .filter(|v| v.is_visible)
-        .count() as i64
+pub fn trees_visible(data: &str) -> Result<i64> {
+    Ok(count_visible(&get_trees_map(data)?))
+}
+
+pub fn max_visible_trees(data: &str) -> Result<i64> {
+    Ok(max_scenic_score(&get_trees_map(data)?))
+}
+
+fn count_visible(trees: &Grid<Tree>) -> i64 {
+    trees.iter().filter(|(_, v)| v.is_visible).count() as i64
 }
 
-pub fn max_visible_trees(data: String) -> i64 {
-    get_trees_map(data)
-        .values()
-        .map(|v| v.left * v.right * v.top * v.bottom)
+fn max_scenic_score(trees: &Grid<Tree>) -> i64 {
+    trees
+        .iter()
+        .map(|(_, v)| v.left * v.right * v.top * v.bottom)
         .max()
-        .unwrap()
+        .unwrap_or(0)
 }
 
-fn is_visible(items: &[i64], item: &i64) -> bool {
+fn is_visible<'a>(items: impl IntoIterator<Item = &'a i64>, item: &i64) -> bool {
     let mut result = true;
-    for other in items.iter() {
+    for other in items {
         if other >= item {
             result = false;
             break;
@@ -35,9 +45,9 @@ fn is_visible(items: &[i64], item: &i64) -> bool {
     result
 }
 
-fn num_visible(items: &[i64], item: &i64) -> i64 {
+fn num_visible<'a>(items: impl IntoIterator<Item = &'a i64>, item: &i64) -> i64 {
     let mut count = 0;
-    for other in items.iter() {
+    for other in items {
         count += 1;
         if other >= item {
             break;
@@ -46,105 +56,74 @@ fn num_visible(items: &[i64], item: &i64) -> i64 {
     count
 }
 
-fn get_trees_map(data: String) -> HashMap<String, Tree> {
-    let rows: Vec<Vec<i64>> = data
-        .lines()
-        .into_iter()
-        .map(|s| {
-            s.split("")
-                .filter(|s| !s.is_empty())
-                .map(|s| s.parse::<i64>().unwrap())
-                .collect()
-        })
-        .collect();
-    let mut trees_visible: HashMap<String, Tree> = HashMap::new();
-
-    for (i, row) in rows.iter().enumerate() {
-        let mut col_idx = 0;
-
-        while col_idx < row.len() {
-            let mut row_clone = row.clone();
-            let left_slice = &mut row_clone[0..col_idx];
-            left_slice.reverse();
-            let right_slice = &row[col_idx + 1..];
-
-            let mut visible_left = true;
-            let mut visible_right = true;
-
-            let num_visible_left = num_visible(left_slice, &row[col_idx]);
-            let num_visible_right = num_visible(right_slice, &row[col_idx]);
-
-            if col_idx > 0 {
-                visible_left = is_visible(left_slice, &row[col_idx]);
-                visible_right = is_visible(right_slice, &row[col_idx]);
-            }
-
-            trees_visible
-                .entry(format!("{},{}", i, col_idx))
-                .and_modify(|e| {
-                    if !e.is_visible {
-                        e.is_visible = visible_left || visible_right;
-                    }
-                    e.right = num_visible_right;
-                    e.left = num_visible_left;
-                })
-                .or_insert(Tree {
-                    is_visible: visible_left || visible_right,
-                    right: num_visible_right,
-                    left: num_visible_left,
-                    top: 0,
-                    bottom: 0,
-                });
-            col_idx += 1;
+fn get_trees_heights(data: &str) -> Result<Grid<i64>> {
+    Grid::parse(DAY, data, |c| match c.to_digit(10) {
+        Some(height) => Ok(height as i64),
+        None => Err("tree heights must be digits".to_owned()),
+    })
+}
+
+fn get_trees_map(data: &str) -> Result<Grid<Tree>> {
+    let heights = get_trees_heights(data)?;
+
+    Ok(heights.map(|point, height| {
+        let ray = |direction| heights.ray(point, direction).map(|(_, h)| h);
+
+        Tree {
+            is_visible: Direction::ALL
+                .into_iter()
+                .any(|direction| is_visible(ray(direction), height)),
+            top: num_visible(ray(Direction::Up), height),
+            bottom: num_visible(ray(Direction::Down), height),
+            left: num_visible(ray(Direction::Left), height),
+            right: num_visible(ray(Direction::Right), height),
         }
+    }))
+}
+
+/// Random puzzle input with a map of `size` rows, at least one, with as many columns except for
+/// some maps of a single column
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let height = size.max(1);
+    let width = if rng.gen_bool(0.1) { 1 } else { height };
+    let mut data = String::new();
+
+    for _ in 0..height {
+        data.extend((0..width).map(|_| char::from(b'0' + rng.gen_range(0..10))));
+        data.push('\n');
     }
 
-    for i in 0..rows[0].len() {
-        let column: Vec<i64> = rows.clone().into_iter().flat_map(|r| [r[i]]).collect();
-        let mut row_idx = 0;
-
-        while row_idx < column.len() {
-            let mut column_clone = column.clone();
-            let top_slice = &mut column_clone[0..row_idx];
-            top_slice.reverse();
-            let bottom_slice = &column[row_idx + 1..];
-
-            let mut visible_top = true;
-            let mut visible_bottom = true;
-
-            let num_visible_top = num_visible(top_slice, &column[row_idx]);
-            let num_visible_bottom = num_visible(bottom_slice, &column[row_idx]);
-
-            if row_idx > 0 {
-                visible_top = is_visible(top_slice, &column[row_idx]);
-                visible_bottom = is_visible(bottom_slice, &column[row_idx]);
-            }
-
-            trees_visible
-                .entry(format!("{},{}", row_idx, i))
-                .and_modify(|e| {
-                    if !e.is_visible {
-                        e.is_visible = visible_top || visible_bottom;
-                    }
-                    e.top = num_visible_top;
-                    e.bottom = num_visible_bottom;
-                })
-                .or_insert(Tree {
-                    is_visible: visible_top || visible_bottom,
-                    top: num_visible_top,
-                    bottom: num_visible_bottom,
-                    right: 0,
-                    left: 0,
-                });
-            row_idx += 1;
-        }
+    data
+}
+
+#[derive(Default)]
+pub struct Day8 {
+    trees: Grid<Tree>,
+}
+
+impl Solution for Day8 {
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.trees = get_trees_map(data)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(count_visible(&self.trees).into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(max_scenic_score(&self.trees).into())
     }
-    trees_visible
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     struct TestCase {
         input: String,
@@ -154,17 +133,11 @@ mod tests {
     #[test]
     fn it_should_return_visible_trees() {
         let test_case = TestCase {
-            input: String::from(
-                "30373
-25512
-65332
-33549
-35390",
-            ),
+            input: String::from(include_str!("../data/examples/day8/example.txt")),
             expected: 21,
         };
 
-        let result = trees_visible(test_case.input);
+        let result = trees_visible(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
@@ -172,17 +145,11 @@ mod tests {
     #[test]
     fn it_should_return_max_visible_trees() {
         let test_case = TestCase {
-            input: String::from(
-                "30373
-25512
-65332
-33549
-35390",
-            ),
+            input: String::from(include_str!("../data/examples/day8/example.txt")),
             expected: 8,
         };
 
-        let result = max_visible_trees(test_case.input);
+        let result = max_visible_trees(&test_case.input).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
@@ -201,4 +168,65 @@ mod tests {
         assert_eq!(result4, 0);
         assert_eq!(result5, 1);
     }
+
+    #[test]
+    fn it_should_report_invalid_maps() {
+        let not_digit = trees_visible("303\n2x5").unwrap_err();
+        let uneven = trees_visible("303\n25").unwrap_err();
+
+        assert_eq!((not_digit.line, not_digit.column), (Some(2), Some(2)));
+        assert_eq!(uneven.line, Some(2));
+        assert!(trees_visible("").is_err());
+    }
+
+    fn heights() -> impl Strategy<Value = Vec<Vec<i64>>> {
+        (1..10usize, 1..10usize).prop_flat_map(|(width, height)| {
+            prop::collection::vec(prop::collection::vec(0..10i64, width), height)
+        })
+    }
+
+    /// Heights met walking from a tree to each edge of the map
+    fn reference_views(heights: &[Vec<i64>], x: usize, y: usize) -> [Vec<i64>; 4] {
+        let row = &heights[y];
+        let column: Vec<i64> = heights.iter().map(|r| r[x]).collect();
+
+        [
+            row[..x].iter().rev().copied().collect(),
+            row[x + 1..].to_vec(),
+            column[..y].iter().rev().copied().collect(),
+            column[y + 1..].to_vec(),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_trees(heights in heights()) {
+            let data = heights
+                .iter()
+                .map(|r| r.iter().map(|h| h.to_string()).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let trees: Vec<(i64, [Vec<i64>; 4])> = (0..heights.len())
+                .flat_map(|y| (0..heights[0].len()).map(move |x| (x, y)))
+                .map(|(x, y)| (heights[y][x], reference_views(&heights, x, y)))
+                .collect();
+            let visible = trees
+                .iter()
+                .filter(|(h, views)| views.iter().any(|v| v.iter().all(|t| t < h)))
+                .count();
+            let scenic = trees
+                .iter()
+                .map(|(h, views)| {
+                    views
+                        .iter()
+                        .map(|v| v.iter().position(|t| t >= h).map_or(v.len(), |p| p + 1) as i64)
+                        .product::<i64>()
+                })
+                .max();
+
+            prop_assert_eq!(trees_visible(&data), Ok(visible as i64));
+            prop_assert_eq!(max_visible_trees(&data).ok(), scenic);
+        }
+    }
 }
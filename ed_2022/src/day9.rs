@@ -1,193 +1,146 @@
-use std::collections::HashMap;
+use aoc::error::{Error, Result};
+use aoc::grid::{Direction, Point, SparseGrid};
+use aoc::parse;
+use aoc::solution::{parse_param_in, unknown_param, Answer, Solution};
+use rand::seq::SliceRandom;
+use rand::Rng;
 
-#[derive(Debug)]
-enum MotionDirection {
-    Right,
-    Left,
-    Up,
-    Down,
-}
+const DAY: u8 = 9;
+/// Most knots a rope can be given through the parameters
+const MAX_KNOTS: i64 = 1000;
 
 #[derive(Debug)]
 struct Motion {
-    direction: MotionDirection,
-    steps: i64,
+    direction: Direction,
+    steps: u32,
 }
 
-#[derive(Debug, Clone)]
-struct Coord {
-    x: i32,
-    y: i32,
+/// Moves a knot next to the previous one, when they are not touching anymore
+fn follow(knot: &mut Point, prev: Point) {
+    let diff = prev - *knot;
+
+    if diff.x.abs() > 1 || diff.y.abs() > 1 {
+        *knot += Point::new(diff.x.signum(), diff.y.signum());
+    }
 }
 
-#[derive(Debug, Clone)]
-struct Knot {
-    coord: Coord,
-    char: String,
+pub fn tail_unique_visits(data: &str, knots_num: i64) -> Result<i64> {
+    simulate_rope(&get_motions(data)?, knots_num)
 }
 
-impl Knot {
-    fn follow(&mut self, prev: &Knot) {
-        let x_diff = prev.coord.x - self.coord.x;
-        if x_diff.abs() > 1 {
-            self.coord.x += if x_diff > 1 { 1 } else { -1 };
+fn simulate_rope(motions: &[Motion], knots_num: i64) -> Result<i64> {
+    if knots_num < 1 {
+        return Err(Error::new(
+            DAY,
+            format!("a rope needs at least one knot, got {}", knots_num),
+        ));
+    }
 
-            self.coord.y = match prev.coord.y.cmp(&self.coord.y) {
-                std::cmp::Ordering::Less => self.coord.y - 1,
-                std::cmp::Ordering::Greater => self.coord.y + 1,
-                std::cmp::Ordering::Equal => self.coord.y,
-            }
-        }
+    let mut knots = vec![Point::ORIGIN; knots_num as usize];
+    let mut tail_visited: SparseGrid<()> = SparseGrid::new();
+    tail_visited.insert(Point::ORIGIN, ());
 
-        let y_diff = prev.coord.y - self.coord.y;
-        if y_diff.abs() > 1 {
-            self.coord.y += if y_diff > 1 { 1 } else { -1 };
+    for motion in motions {
+        for _ in 0..motion.steps {
+            knots[0] = knots[0].step(motion.direction);
 
-            self.coord.x = match prev.coord.x.cmp(&self.coord.x) {
-                std::cmp::Ordering::Less => self.coord.x - 1,
-                std::cmp::Ordering::Greater => self.coord.x + 1,
-                std::cmp::Ordering::Equal => self.coord.x,
+            for i in 1..knots.len() {
+                let prev = knots[i - 1];
+                follow(&mut knots[i], prev);
             }
+
+            tail_visited.insert(*knots.last().unwrap(), ());
         }
     }
+    Ok(tail_visited.len() as i64)
 }
 
-#[derive(Debug)]
-struct Board {
-    max: Coord,
-    min: Coord,
-}
-
-pub fn tail_unique_visits(data: String, knots_num: i64) -> i64 {
-    let motions: Vec<Motion> = get_motions(data);
+fn get_motions(data: &str) -> Result<Vec<Motion>> {
+    parse::lines(DAY, data)
+        .map(|line| {
+            let [direction, steps] =
+                line.split(line.text, ' ', "expected a direction and steps")?;
 
-    let mut knots = get_initial_knots(knots_num);
-    let mut board = Board {
-        min: Coord { x: 0, y: 0 },
-        max: Coord { x: 0, y: 0 },
-    };
-    let mut tail_visited = HashMap::from([("0,0".to_owned(), true)]);
+            Ok(Motion {
+                steps: line.parse(steps)?,
+                direction: match direction {
+                    "R" => Direction::Right,
+                    "L" => Direction::Left,
+                    "U" => Direction::Up,
+                    "D" => Direction::Down,
+                    _ => return Err(line.error(1, "not valid motion direction")),
+                },
+            })
+        })
+        .collect()
+}
 
-    for motion in motions {
-        for _ in 0..motion.steps {
-            let knots_clone = knots.clone();
-            let mut head = knots.first_mut().unwrap();
-            let mut prev_knot = knots_clone.first().unwrap();
-
-            match motion.direction {
-                MotionDirection::Up => {
-                    if head.coord.y == board.min.y {
-                        board.min.y -= 1;
-                    }
-                    head.coord.y -= 1;
-                }
-                MotionDirection::Down => {
-                    if head.coord.y == board.max.y {
-                        board.max.y += 1;
-                    }
-                    head.coord.y += 1;
-                }
-                MotionDirection::Right => {
-                    if head.coord.x == board.max.x {
-                        board.max.x += 1;
-                    }
-                    head.coord.x += 1;
-                }
-                MotionDirection::Left => {
-                    if head.coord.x == board.min.x {
-                        board.min.x -= 1;
-                    }
-                    head.coord.x -= 1;
-                }
-            }
+/// Random puzzle input with `size` motions, long enough to fully stretch a rope of ten knots
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let direction = ['R', 'L', 'U', 'D'].choose(rng).unwrap();
+            format!("{} {}\n", direction, rng.gen_range(1..=20))
+        })
+        .collect()
+}
 
-            for knot in knots.iter_mut() {
-                if knot.char != "H" {
-                    knot.follow(prev_knot);
-                }
-                prev_knot = knot;
-            }
+pub struct Day9 {
+    motions: Vec<Motion>,
+    part1_knots: i64,
+    part2_knots: i64,
+}
 
-            tail_visited.insert(
-                format!(
-                    "{},{}",
-                    knots.last().unwrap().coord.x,
-                    knots.last().unwrap().coord.y
-                ),
-                true,
-            );
+impl Default for Day9 {
+    fn default() -> Self {
+        Day9 {
+            motions: vec![],
+            part1_knots: 2,
+            part2_knots: 10,
         }
-        // println!("-- final --");
-        // print_rope_map(&board, &knots, &tail_visited);
     }
-    tail_visited.keys().len() as i64
 }
 
-// fn print_rope_map(board: &Board, knots: &Vec<Knot>, visited: &HashMap<String, bool>) {
-//     for y in board.min.y..board.max.y + 1 {
-//         for x in board.min.x..board.max.x + 1 {
-//             let mut item = ".";
-//
-//             if visited.contains_key(&format!("{},{}", x, y)) {
-//                 item = "#";
-//             }
-//
-//             for knot in knots.iter().rev() {
-//                 if knot.coord.y == y && knot.coord.x == x {
-//                     item = &knot.char;
-//                 }
-//             }
-//
-//             print!("{}", item);
-//         }
-//         println!("")
-//     }
-// }
-
-fn get_initial_knots(num: i64) -> Vec<Knot> {
-    let mut knots: Vec<Knot> = vec![];
-    for i in 0..num {
-        if i == 0 {
-            knots.push(Knot {
-                coord: Coord { x: 0, y: 0 },
-                char: "H".to_string(),
-            });
-        } else if i == num - 1 {
-            knots.push(Knot {
-                coord: Coord { x: 0, y: 0 },
-                char: "T".to_string(),
-            });
-        } else {
-            knots.push(Knot {
-                coord: Coord { x: 0, y: 0 },
-                char: format!("{}", i),
-            });
-        }
+impl Solution for Day9 {
+    fn day(&self) -> u8 {
+        9
     }
-    knots
-}
 
-fn get_motions(data: String) -> Vec<Motion> {
-    data.lines()
-        .map(|l| {
-            let line_parts: Vec<&str> = l.split(' ').collect();
-            Motion {
-                steps: line_parts.get(1).unwrap().parse::<i64>().unwrap(),
-                direction: match *line_parts.first().unwrap() {
-                    "R" => MotionDirection::Right,
-                    "L" => MotionDirection::Left,
-                    "U" => MotionDirection::Up,
-                    "D" => MotionDirection::Down,
-                    _ => panic!("not valid motion direction"),
-                },
-            }
-        })
-        .collect()
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.motions = get_motions(data)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(simulate_rope(&self.motions, self.part1_knots)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(simulate_rope(&self.motions, self.part2_knots)?.into())
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("part1_knots", self.part1_knots.to_string()),
+            ("part2_knots", self.part2_knots.to_string()),
+        ]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "part1_knots" => self.part1_knots = parse_param_in(DAY, name, value, 1..=MAX_KNOTS)?,
+            "part2_knots" => self.part2_knots = parse_param_in(DAY, name, value, 1..=MAX_KNOTS)?,
+            _ => return Err(unknown_param(DAY, name)),
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
 
     struct TestCase {
         input: (String, i64),
@@ -198,22 +151,13 @@ mod tests {
     fn it_should_return_tail_unique_visits_two_knots() {
         let test_case = TestCase {
             input: (
-                String::from(
-                    "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2",
-                ),
+                String::from(include_str!("../data/examples/day9/example.txt")),
                 2,
             ),
             expected: 13,
         };
 
-        let result = tail_unique_visits(test_case.input.0, test_case.input.1);
+        let result = tail_unique_visits(&test_case.input.0, test_case.input.1).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
@@ -222,22 +166,13 @@ R 2",
     fn it_should_return_tail_unique_visits_nine_knots() {
         let test_case = TestCase {
             input: (
-                String::from(
-                    "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2",
-                ),
+                String::from(include_str!("../data/examples/day9/example.txt")),
                 10,
             ),
             expected: 1,
         };
 
-        let result = tail_unique_visits(test_case.input.0, test_case.input.1);
+        let result = tail_unique_visits(&test_case.input.0, test_case.input.1).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
@@ -246,23 +181,94 @@ R 2",
     fn it_should_return_tail_unique_visits_nine_knots_large() {
         let test_case = TestCase {
             input: (
-                String::from(
-                    "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20",
-                ),
+                String::from(include_str!("../data/examples/day9/larger.txt")),
                 10,
             ),
             expected: 36,
         };
 
-        let result = tail_unique_visits(test_case.input.0, test_case.input.1);
+        let result = tail_unique_visits(&test_case.input.0, test_case.input.1).unwrap();
 
         assert_eq!(result, test_case.expected);
     }
+
+    #[test]
+    fn it_should_report_invalid_motions() {
+        let direction = tail_unique_visits("R 4\nX 4", 2).unwrap_err();
+        let steps = tail_unique_visits("R 4\nU four", 2).unwrap_err();
+
+        assert_eq!((direction.line, direction.column), (Some(2), Some(1)));
+        assert_eq!((steps.line, steps.column), (Some(2), Some(3)));
+        assert!(tail_unique_visits("R 4\nU -3", 2).is_err());
+        assert!(tail_unique_visits("R 4294967296", 2).is_err());
+        assert_eq!(tail_unique_visits("R 20000", 2), Ok(20000));
+        assert!(tail_unique_visits("R 4", 0).is_err());
+    }
+
+    #[test]
+    fn it_should_drag_long_ropes_diagonally() {
+        // The head goes 5 to the right, then 5 up: the tail of 4 knots reaches (2, 0), then
+        // follows the diagonal (3, -1), (4, -2)
+        assert_eq!(tail_unique_visits("R 5\nU 5", 4), Ok(5));
+        assert_eq!(tail_unique_visits("R 5\nU 5", 10), Ok(1));
+    }
+
+    /// Motions as the letter of their direction and their number of steps
+    fn motions() -> impl Strategy<Value = Vec<(char, i64)>> {
+        prop::collection::vec(
+            (prop::sample::select(vec!['R', 'L', 'U', 'D']), 1..10i64),
+            0..40,
+        )
+    }
+
+    /// Positions visited by the tail, moving every knot one step at a time. A knot no longer
+    /// touching the previous one moves to the cell around it closest to that knot, and must then
+    /// touch it.
+    fn reference_visits(motions: &[(char, i64)], knots: usize) -> i64 {
+        let mut rope = vec![(0i64, 0i64); knots];
+        let mut visited = HashSet::from([(0, 0)]);
+
+        for (direction, steps) in motions {
+            for _ in 0..*steps {
+                match direction {
+                    'R' => rope[0].0 += 1,
+                    'L' => rope[0].0 -= 1,
+                    'U' => rope[0].1 -= 1,
+                    _ => rope[0].1 += 1,
+                }
+                for i in 1..knots {
+                    let (x, y) = rope[i - 1];
+                    let touching =
+                        |(kx, ky): (i64, i64)| (kx - x).abs() <= 1 && (ky - y).abs() <= 1;
+                    if !touching(rope[i]) {
+                        let (kx, ky) = rope[i];
+                        rope[i] = (kx - 1..=kx + 1)
+                            .flat_map(|nx| (ky - 1..=ky + 1).map(move |ny| (nx, ny)))
+                            .min_by_key(|(nx, ny)| (nx - x).pow(2) + (ny - y).pow(2))
+                            .unwrap();
+                    }
+                    assert!(touching(rope[i]), "knot {} left behind", i);
+                }
+                visited.insert(rope[knots - 1]);
+            }
+        }
+
+        visited.len() as i64
+    }
+
+    proptest! {
+        #[test]
+        fn it_should_match_the_reference_rope(motions in motions(), knots in 1..=10usize) {
+            let data = motions
+                .iter()
+                .map(|(d, s)| format!("{} {}", d, s))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            prop_assert_eq!(
+                tail_unique_visits(&data, knots as i64),
+                Ok(reference_visits(&motions, knots))
+            );
+        }
+    }
 }
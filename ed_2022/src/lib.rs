@@ -9,3 +9,160 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+
+use aoc::{Event, Solution};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Directory with the puzzle inputs and recorded answers of 2022
+pub const DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data");
+
+/// Advent of code 2022
+pub fn event() -> Event {
+    Event::new(2022, env!("CARGO_MANIFEST_DIR"), solutions).with_generator(generate)
+}
+
+/// All the available solutions, ordered by day
+pub fn solutions() -> Vec<Box<dyn Solution>> {
+    vec![
+        Box::<day1::Day1>::default(),
+        Box::<day2::Day2>::default(),
+        Box::<day3::Day3>::default(),
+        Box::<day4::Day4>::default(),
+        Box::<day5::Day5>::default(),
+        Box::<day6::Day6>::default(),
+        Box::<day7::Day7>::default(),
+        Box::<day8::Day8>::default(),
+        Box::<day9::Day9>::default(),
+        Box::<day10::Day10>::default(),
+        Box::<day11::Day11>::default(),
+    ]
+}
+
+/// Solution for the given day, if there is one
+pub fn get_solution(day: u8) -> Option<Box<dyn Solution>> {
+    solutions().into_iter().find(|s| s.day() == day)
+}
+
+/// Random puzzle input of a day, the same for the same size and seed. The size is roughly the
+/// number of lines or items of the input, see the `generate` function of each day.
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+    let rng = &mut ChaCha8Rng::seed_from_u64(seed);
+
+    match day {
+        1 => Some(day1::generate(rng, size)),
+        2 => Some(day2::generate(rng, size)),
+        3 => Some(day3::generate(rng, size)),
+        4 => Some(day4::generate(rng, size)),
+        5 => Some(day5::generate(rng, size)),
+        6 => Some(day6::generate(rng, size)),
+        7 => Some(day7::generate(rng, size)),
+        8 => Some(day8::generate(rng, size)),
+        9 => Some(day9::generate(rng, size)),
+        10 => Some(day10::generate(rng, size)),
+        11 => Some(day11::generate(rng, size)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::parse;
+    use aoc::Answer;
+    use proptest::prelude::*;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn it_should_register_solutions_in_day_order() {
+        let days: Vec<u8> = solutions().iter().map(|s| s.day()).collect();
+
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", days);
+        assert!(days.iter().all(|day| (1..=25).contains(day)), "{:?}", days);
+    }
+
+    #[test]
+    fn it_should_get_solution_by_day() {
+        let mut solution = get_solution(4).unwrap();
+        solution.parse("2-4,6-8\n2-8,3-7\n6-6,4-6").unwrap();
+
+        assert_eq!(solution.solve(1), Ok(Answer::Number(2)));
+        assert!(solution.solve(3).is_err());
+        assert!(get_solution(25).is_none());
+    }
+
+    #[test]
+    fn it_should_generate_valid_inputs() {
+        for seed in 0..20 {
+            for mut solution in solutions() {
+                let day = solution.day();
+                let Some(data) = generate(day, 50, seed) else {
+                    continue;
+                };
+
+                solution.parse(&data).unwrap();
+                assert!(solution.solve(1).is_ok(), "day {} seed {}", day, seed);
+                assert!(solution.solve(2).is_ok(), "day {} seed {}", day, seed);
+                assert_eq!(generate(day, 50, seed), Some(data));
+            }
+        }
+        assert!(generate(25, 50, 0).is_none());
+    }
+
+    /// Edits of an input, each removing a few bytes at some position and inserting a short text
+    /// or an extreme integer
+    fn edits() -> impl Strategy<Value = Vec<(prop::sample::Index, usize, String)>> {
+        let extremes = vec![
+            "9223372036854775807",
+            "-9223372036854775808",
+            "4294967295",
+            "3000000000",
+        ];
+        let text = prop_oneof![
+            r"[0-9a-z \n$:,=+*/\[\]-]{0,2}",
+            prop::sample::select(extremes).prop_map(String::from),
+        ];
+        prop::collection::vec((any::<prop::sample::Index>(), 0..4usize, text), 1..5)
+    }
+
+    /// Days having a solution
+    fn days() -> impl Strategy<Value = u8> {
+        prop::sample::select(solutions().iter().map(|s| s.day()).collect::<Vec<_>>())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        #[test]
+        fn it_should_handle_malformed_inputs(day in days(), seed: u64, edits in edits()) {
+            let Some(mut data) = generate(day, 10, seed) else {
+                return Ok(());
+            };
+            for (position, removed, inserted) in edits {
+                let start = position.index(data.len() + 1);
+                data.replace_range(start..(start + removed).min(data.len()), &inserted);
+            }
+            // Long rope motions are valid but take as many steps to simulate
+            let long_motion = parse::integers(&data)
+                .iter()
+                .any(|n| n.parse::<u32>().is_ok_and(|n| n > 10_000));
+            if day == 9 && long_motion {
+                return Ok(());
+            }
+
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                let mut solution = get_solution(day).unwrap();
+                if solution.parse(&data).is_ok() {
+                    let _ = (solution.solve(1), solution.solve(2));
+                }
+                sender.send(()).unwrap();
+            });
+
+            // Fails when the solution panics, dropping the sender, or takes too long
+            prop_assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok());
+        }
+    }
+}
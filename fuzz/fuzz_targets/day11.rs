@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    let mut solution = ed_2022::get_solution(11).unwrap();
+
+    if solution.parse(data).is_ok() {
+        let _ = solution.solve(1);
+        let _ = solution.solve(2);
+    }
+});
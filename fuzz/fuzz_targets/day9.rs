@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+/// Longest motion simulated, as the time taken grows with the number of steps
+const MAX_STEPS: u32 = 10_000;
+
+fuzz_target!(|data: &str| {
+    let long_motion = aoc::parse::integers(data)
+        .iter()
+        .any(|n| n.parse::<u32>().is_ok_and(|n| n > MAX_STEPS));
+    let mut solution = ed_2022::get_solution(9).unwrap();
+
+    if !long_motion && solution.parse(data).is_ok() {
+        let _ = solution.solve(1);
+        let _ = solution.solve(2);
+    }
+});
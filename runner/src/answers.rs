@@ -0,0 +1,169 @@
+use crate::run::PartRun;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the answers file inside a data directory
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Known-correct answers, recorded as a `[dayN]` table with `part1` and `part2` keys
+#[derive(Debug, Default)]
+pub struct Answers {
+    table: toml::Table,
+}
+
+impl Answers {
+    pub fn parse(contents: &str) -> Result<Answers, toml::de::Error> {
+        Ok(Answers {
+            table: contents.parse()?,
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Answers> {
+        let contents = fs::read_to_string(path)?;
+
+        Answers::parse(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Recorded answer for a part of a day, if there is one
+    pub fn get(&self, day: u8, part: u8) -> Option<String> {
+        let value = self
+            .table
+            .get(&format!("day{}", day))?
+            .get(format!("part{}", part))?;
+
+        Some(answer_of(value))
+    }
+}
+
+/// Answer recorded in a TOML value, either a number or a string
+pub fn answer_of(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.to_owned(),
+        other => other.to_string(),
+    }
+}
+
+/// Path of the answers file inside a data directory
+pub fn answers_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(ANSWERS_FILE)
+}
+
+/// Result of checking a run against the recorded answers
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing,
+    Error(String),
+}
+
+pub fn verify(run: &PartRun, answers: &Answers) -> Verdict {
+    check(run, answers.get(run.day, run.part))
+}
+
+/// Checks a run against the answer it is expected to give, if there is one
+pub fn check(run: &PartRun, expected: Option<String>) -> Verdict {
+    let actual = match &run.answer {
+        Ok(answer) => answer.to_string(),
+        Err(err) => return Verdict::Error(err.to_string()),
+    };
+
+    match expected {
+        Some(expected) if expected == actual => Verdict::Pass,
+        Some(expected) => Verdict::Fail { expected, actual },
+        None => Verdict::Missing,
+    }
+}
+
+/// Line by line diff between an expected and an actual answer
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let mut diff: Vec<String> = vec![];
+
+    for i in 0..expected_lines.len().max(actual_lines.len()) {
+        match (expected_lines.get(i), actual_lines.get(i)) {
+            (Some(e), Some(a)) if e == a => diff.push(format!("  {}", e)),
+            (e, a) => {
+                if let Some(e) = e {
+                    diff.push(format!("- {}", e));
+                }
+                if let Some(a) = a {
+                    diff.push(format!("+ {}", a));
+                }
+            }
+        }
+    }
+
+    diff.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::Answer;
+    use std::time::Duration;
+
+    fn get_answers() -> Answers {
+        Answers::parse(
+            r#"
+[day5]
+part1 = "CMZ"
+part2 = 12
+
+[day10]
+part2 = """
+##..
+#.#.
+"""
+"#,
+        )
+        .unwrap()
+    }
+
+    fn get_run(day: u8, part: u8, answer: Answer) -> PartRun {
+        PartRun {
+            day,
+            part,
+            answer: Ok(answer),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn it_should_get_recorded_answers() {
+        let answers = get_answers();
+
+        assert_eq!(answers.get(5, 1), Some("CMZ".to_owned()));
+        assert_eq!(answers.get(5, 2), Some("12".to_owned()));
+        assert_eq!(answers.get(10, 2), Some("##..\n#.#.\n".to_owned()));
+        assert_eq!(answers.get(10, 1), None);
+    }
+
+    #[test]
+    fn it_should_verify_runs() {
+        let answers = get_answers();
+        let crt = Answer::Text("##..\n#.#.\n".to_owned());
+
+        assert_eq!(verify(&get_run(10, 2, crt), &answers), Verdict::Pass);
+        assert_eq!(
+            verify(&get_run(5, 2, Answer::Number(13)), &answers),
+            Verdict::Fail {
+                expected: "12".to_owned(),
+                actual: "13".to_owned()
+            }
+        );
+        assert_eq!(
+            verify(&get_run(6, 1, Answer::Number(7)), &answers),
+            Verdict::Missing
+        );
+    }
+
+    #[test]
+    fn it_should_diff_answers() {
+        let result = diff("##..\n#.#.\n", "##..\n#..#\n");
+
+        assert_eq!(result, "  ##..\n- #.#.\n+ #..#");
+    }
+}
@@ -0,0 +1,258 @@
+use aoc::{Solution, PARTS};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Step of a solution being timed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Part(u8),
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part(part) => write!(f, "part{}", part),
+        }
+    }
+}
+
+/// Summary of the timings of a phase
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        if samples.is_empty() {
+            return Stats {
+                min: Duration::ZERO,
+                median: Duration::ZERO,
+                mean: Duration::ZERO,
+                stddev: Duration::ZERO,
+            };
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        let secs: Vec<f64> = sorted.iter().map(|s| s.as_secs_f64()).collect();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / secs.len() as f64;
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Timings of every phase of a day
+#[derive(Debug)]
+pub struct DayBench {
+    pub day: u8,
+    pub phases: Vec<(Phase, Stats)>,
+}
+
+/// Times parsing and solving each part of a day `runs` times, after `warmup` untimed runs
+pub fn bench_day(
+    solution: &mut dyn Solution,
+    data: &str,
+    runs: usize,
+    warmup: usize,
+) -> aoc::Result<DayBench> {
+    for _ in 0..warmup {
+        solution.parse(data)?;
+        for part in PARTS {
+            solution.solve(part)?;
+        }
+    }
+
+    let mut parse_samples: Vec<Duration> = vec![];
+    let mut part_samples: Vec<Vec<Duration>> = vec![vec![]; PARTS.len()];
+    for _ in 0..runs {
+        let start = Instant::now();
+        solution.parse(data)?;
+        parse_samples.push(start.elapsed());
+
+        for (i, part) in PARTS.iter().enumerate() {
+            let start = Instant::now();
+            solution.solve(*part)?;
+            part_samples[i].push(start.elapsed());
+        }
+    }
+
+    let mut phases = vec![(Phase::Parse, Stats::from_samples(&parse_samples))];
+    for (part, samples) in PARTS.iter().zip(part_samples) {
+        phases.push((Phase::Part(*part), Stats::from_samples(&samples)));
+    }
+
+    Ok(DayBench {
+        day: solution.day(),
+        phases,
+    })
+}
+
+/// Median timings saved from a previous benchmark, as nanoseconds in a `[dayN]` table
+#[derive(Debug, Default)]
+pub struct Baseline {
+    table: toml::Table,
+}
+
+impl Baseline {
+    pub fn from_benches(benches: &[DayBench]) -> Baseline {
+        let mut table = toml::Table::new();
+
+        for bench in benches {
+            let mut day = toml::Table::new();
+            for (phase, stats) in &bench.phases {
+                day.insert(
+                    phase.to_string(),
+                    toml::Value::Integer(stats.median.as_nanos() as i64),
+                );
+            }
+            table.insert(format!("day{}", bench.day), toml::Value::Table(day));
+        }
+
+        Baseline { table }
+    }
+
+    pub fn parse(contents: &str) -> Result<Baseline, toml::de::Error> {
+        Ok(Baseline {
+            table: contents.parse()?,
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Baseline> {
+        let contents = fs::read_to_string(path)?;
+
+        Baseline::parse(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.table.to_string())
+    }
+
+    /// Median recorded for a phase of a day, if there is one
+    pub fn median(&self, day: u8, phase: Phase) -> Option<Duration> {
+        let nanos = self
+            .table
+            .get(&format!("day{}", day))?
+            .get(phase.to_string())?
+            .as_integer()?;
+
+        Some(Duration::from_nanos(nanos.max(0) as u64))
+    }
+}
+
+/// Median of a phase compared with its baseline
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub day: u8,
+    pub phase: Phase,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// Change from the baseline, in percent
+    pub change: f64,
+}
+
+impl Comparison {
+    /// Whether the phase got slower by more than `threshold` percent
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change > threshold
+    }
+}
+
+/// Compares the medians of every phase that has a baseline
+pub fn compare(benches: &[DayBench], baseline: &Baseline) -> Vec<Comparison> {
+    let mut comparisons: Vec<Comparison> = vec![];
+
+    for bench in benches {
+        for (phase, stats) in &bench.phases {
+            let Some(base) = baseline.median(bench.day, *phase) else {
+                continue;
+            };
+            let change = if base.is_zero() {
+                0.0
+            } else {
+                (stats.median.as_secs_f64() / base.as_secs_f64() - 1.0) * 100.0
+            };
+
+            comparisons.push(Comparison {
+                day: bench.day,
+                phase: *phase,
+                baseline: base,
+                current: stats.median,
+                change,
+            });
+        }
+    }
+
+    comparisons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed_2022::get_solution;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn it_should_summarize_samples() {
+        let stats = Stats::from_samples(&[ms(4), ms(1), ms(3), ms(2)]);
+
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean.as_micros(), 2500);
+        assert_eq!(stats.stddev.as_micros(), 1118);
+    }
+
+    #[test]
+    fn it_should_bench_every_phase() {
+        let mut solution = get_solution(6).unwrap();
+
+        let bench = bench_day(solution.as_mut(), "mjqjpqmgbljsphdztnvjfqwrcgsmlb", 3, 1).unwrap();
+
+        let phases: Vec<Phase> = bench.phases.iter().map(|(p, _)| *p).collect();
+        assert_eq!(phases, [Phase::Parse, Phase::Part(1), Phase::Part(2)]);
+    }
+
+    #[test]
+    fn it_should_compare_against_baseline() {
+        let benches = [DayBench {
+            day: 3,
+            phases: vec![
+                (Phase::Parse, Stats::from_samples(&[ms(10)])),
+                (Phase::Part(1), Stats::from_samples(&[ms(30)])),
+            ],
+        }];
+        let baseline = Baseline::parse("[day3]\nparse = 10000000\npart1 = 20000000\n").unwrap();
+
+        let comparisons = compare(&benches, &baseline);
+
+        assert_eq!(comparisons.len(), 2);
+        assert!(!comparisons[0].is_regression(10.0));
+        assert!(comparisons[1].is_regression(10.0));
+        assert_eq!(comparisons[1].change.round(), 50.0);
+    }
+}
@@ -0,0 +1,324 @@
+use crate::run;
+use crate::submit::{self, Outcome};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const USER_AGENT: &str = concat!("aoc-runner/", env!("CARGO_PKG_VERSION"));
+
+/// Keeps requests at least `interval` apart, even across runs, by recording the time of the last
+/// one in a file
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    path: PathBuf,
+    interval: Duration,
+}
+
+impl Throttle {
+    pub fn new(path: impl Into<PathBuf>, interval: Duration) -> Throttle {
+        Throttle {
+            path: path.into(),
+            interval,
+        }
+    }
+
+    /// Waits until `interval` has passed since the last request, then records a new one
+    pub fn wait(&self) -> io::Result<()> {
+        let last = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+
+        if let Some(elapsed) = last.and_then(|l| l.elapsed().ok()) {
+            if elapsed < self.interval {
+                thread::sleep(self.interval - elapsed);
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        fs::write(&self.path, now.as_millis().to_string())
+    }
+}
+
+/// Where the input of a day came from
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    /// The input file already existed and was left untouched
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Client of the advent of code website
+pub struct Client {
+    base_url: String,
+    session: String,
+    throttle: Throttle,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str, throttle: Throttle) -> Client {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(30)))
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        Client {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+            throttle,
+            agent,
+        }
+    }
+
+    /// Downloads the puzzle input of a day
+    pub fn input(&self, year: u16, day: u8) -> io::Result<String> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+
+        self.throttle.wait()?;
+        let response = self
+            .agent
+            .get(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .header("User-Agent", USER_AGENT)
+            .call();
+
+        body_of(&url, response)
+    }
+
+    /// Submits the answer of a part, returning the reply of the website
+    pub fn submit(&self, year: u16, day: u8, part: u8, answer: &str) -> io::Result<Outcome> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+
+        self.throttle.wait()?;
+        let response = self
+            .agent
+            .post(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .header("User-Agent", USER_AGENT)
+            .send_form([("level", part.to_string().as_str()), ("answer", answer)]);
+
+        Ok(submit::parse_outcome(&body_of(&url, response)?))
+    }
+
+    /// Saves the input of a day in the data directory, unless it is already there. An empty file,
+    /// like the placeholder written by `aoc new`, does not count as an input.
+    pub fn fetch_input(&self, year: u16, day: u8, data_dir: &Path) -> io::Result<Fetched> {
+        let path = run::input_path(data_dir, day);
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let input = self.input(year, day)?;
+
+        // Write to a temporary file first so an interrupted download never leaves a partial input
+        let partial = path.with_extension("txt.partial");
+        fs::create_dir_all(data_dir)?;
+        fs::write(&partial, input)?;
+        fs::rename(&partial, &path)?;
+
+        Ok(Fetched::Downloaded(path))
+    }
+}
+
+/// Body of a successful response
+fn body_of(
+    url: &str,
+    response: Result<ureq::http::Response<ureq::Body>, ureq::Error>,
+) -> io::Result<String> {
+    let mut response = response.map_err(ureq::Error::into_io)?;
+
+    let status = response.status();
+    let body = response
+        .body_mut()
+        .read_to_string()
+        .map_err(ureq::Error::into_io)?;
+    if !status.is_success() {
+        return Err(io::Error::other(format!(
+            "{} answered {}: {}",
+            url,
+            status,
+            body.trim()
+        )));
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scaffold;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+    use std::time::Instant;
+
+    /// Stand-in server answering the given responses, one per connection, in order.
+    /// Returns its base URL and a handle giving back the requests it received.
+    fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests: Vec<String> = vec![];
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request += &line;
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                request += &String::from_utf8(body).unwrap();
+
+                stream.write_all(response.as_bytes()).unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+
+        (base_url, handle)
+    }
+
+    fn response(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn it_should_fetch_inputs_once() {
+        let dir = temp_dir("fetch");
+        let (base_url, server) = serve(vec![response("200 OK", "1000\n2000\n")]);
+        let client = Client::new(
+            &base_url,
+            "53616c",
+            Throttle::new(dir.join("last-request"), Duration::ZERO),
+        );
+
+        let first = client.fetch_input(2022, 1, &dir).unwrap();
+        let second = client.fetch_input(2022, 1, &dir).unwrap();
+
+        let path = dir.join("day1_input.txt");
+        assert_eq!(first, Fetched::Downloaded(path.to_owned()));
+        assert_eq!(second, Fetched::Cached(path.to_owned()));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n2000\n");
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2022/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("session=53616c"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn it_should_fetch_inputs_of_new_days() {
+        let dir = temp_dir("fetch-new");
+        let data_dir = dir.join("data");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(
+            dir.join("src").join("lib.rs"),
+            "pub mod day1;\n\nvec![\n    Box::<day1::Day1>::default(),\n]\n",
+        )
+        .unwrap();
+        let (base_url, server) = serve(vec![response("200 OK", "A Y\n")]);
+        let client = Client::new(
+            &base_url,
+            "53616c",
+            Throttle::new(dir.join("last-request"), Duration::ZERO),
+        );
+
+        scaffold::new_day(&dir, &data_dir, 2).unwrap();
+        let fetched = client.fetch_input(2022, 2, &data_dir).unwrap();
+
+        let path = data_dir.join("day2_input.txt");
+        assert_eq!(fetched, Fetched::Downloaded(path.to_owned()));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "A Y\n");
+
+        server.join().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn it_should_not_save_failed_downloads() {
+        let dir = temp_dir("fetch-failed");
+        let (base_url, server) = serve(vec![response("404 Not Found", "not unlocked yet")]);
+        let client = Client::new(
+            &base_url,
+            "53616c",
+            Throttle::new(dir.join("last-request"), Duration::ZERO),
+        );
+
+        let result = client.fetch_input(2022, 25, &dir);
+
+        assert!(result.unwrap_err().to_string().contains("404"));
+        assert!(!dir.join("day25_input.txt").exists());
+
+        server.join().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn it_should_submit_answers() {
+        let dir = temp_dir("submit");
+        let (base_url, server) = serve(vec![response(
+            "200 OK",
+            "<article><p>That's not the right answer; your answer is too low.</p></article>",
+        )]);
+        let client = Client::new(
+            &base_url,
+            "53616c",
+            Throttle::new(dir.join("last-request"), Duration::ZERO),
+        );
+
+        let outcome = client.submit(2022, 5, 2, "HRFTQVWNN").unwrap();
+
+        assert_eq!(outcome, Outcome::TooLow);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2022/day/5/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=HRFTQVWNN"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn it_should_throttle_requests() {
+        let dir = temp_dir("throttle");
+        let throttle = Throttle::new(dir.join("last-request"), Duration::from_millis(200));
+
+        let start = Instant::now();
+        throttle.wait().unwrap();
+        throttle.wait().unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,75 @@
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Address of the advent of code website
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Time to wait between two requests to the website
+pub const DEFAULT_THROTTLE: Duration = Duration::from_secs(5);
+
+/// Settings to talk to the advent of code website, read from a TOML file
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Value of the `session` cookie of a logged in user
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+    /// Seconds to wait between two requests
+    pub throttle: Option<u64>,
+}
+
+impl Config {
+    pub fn parse(contents: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Reads the config file, with no settings when it does not exist
+    pub fn load(path: &Path) -> io::Result<Config> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(err) => return Err(err),
+        };
+
+        Config::parse(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL)
+    }
+
+    pub fn throttle(&self) -> Duration {
+        self.throttle.map_or(DEFAULT_THROTTLE, Duration::from_secs)
+    }
+}
+
+/// Path of the config file when no other is given, `~/.config/aoc/config.toml`
+pub fn default_config_path() -> Option<PathBuf> {
+    let home = env::var_os("HOME")?;
+
+    Some(
+        PathBuf::from(home)
+            .join(".config")
+            .join("aoc")
+            .join("config.toml"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_config() {
+        let config = Config::parse("session = \"53616c\"\nthrottle = 1\n").unwrap();
+
+        assert_eq!(config.session.as_deref(), Some("53616c"));
+        assert_eq!(config.base_url(), DEFAULT_BASE_URL);
+        assert_eq!(config.throttle(), Duration::from_secs(1));
+        assert!(Config::parse("sesion = \"53616c\"").is_err());
+    }
+}
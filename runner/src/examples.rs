@@ -0,0 +1,134 @@
+use crate::answers::{self, Verdict};
+use crate::run::{self, PartRun};
+use aoc::{Solution, PARTS};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Example input of a day, read from `examples/dayN/NAME.txt` inside a data directory, with the
+/// answers it should give recorded as `part1` and `part2` keys in `NAME.toml` next to it
+#[derive(Debug)]
+pub struct Example {
+    pub day: u8,
+    pub name: String,
+    pub path: PathBuf,
+    expected: toml::Table,
+}
+
+impl Example {
+    pub fn load(day: u8, path: &Path) -> io::Result<Example> {
+        let answers_path = path.with_extension("toml");
+        let expected = match fs::read_to_string(&answers_path) {
+            Ok(contents) => contents
+                .parse()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => toml::Table::new(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Example {
+            day,
+            name: path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            path: path.to_owned(),
+            expected,
+        })
+    }
+
+    /// Answer the example should give for a part, if it is recorded
+    pub fn expected(&self, part: u8) -> Option<String> {
+        self.expected
+            .get(&format!("part{}", part))
+            .map(answers::answer_of)
+    }
+}
+
+/// Directory with the examples of a day inside a data directory
+pub fn examples_dir(data_dir: &Path, day: u8) -> PathBuf {
+    data_dir.join("examples").join(format!("day{}", day))
+}
+
+/// Every example inside a data directory, ordered by day and name
+pub fn discover(data_dir: &Path) -> io::Result<Vec<Example>> {
+    let mut examples: Vec<Example> = vec![];
+
+    let entries = match fs::read_dir(data_dir.join("examples")) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(examples),
+        Err(err) => return Err(err),
+    };
+    for entry in entries {
+        let dir = entry?.path();
+        let Some(day) = dir
+            .file_name()
+            .and_then(|n| n.to_str()?.strip_prefix("day")?.parse::<u8>().ok())
+        else {
+            continue;
+        };
+
+        for file in fs::read_dir(&dir)? {
+            let path = file?.path();
+            if path.extension().is_some_and(|e| e == "txt") {
+                examples.push(Example::load(day, &path)?);
+            }
+        }
+    }
+
+    examples.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(examples)
+}
+
+/// Solves every part of an example that has a recorded answer, checking it
+pub fn check(
+    example: &Example,
+    solution: &mut dyn Solution,
+) -> io::Result<Vec<(PartRun, Verdict)>> {
+    let data = fs::read_to_string(&example.path)?;
+    let mut checks: Vec<(PartRun, Verdict)> = vec![];
+
+    for part in PARTS {
+        let Some(expected) = example.expected(part) else {
+            continue;
+        };
+        let run = run::run_part(solution, &data, part);
+        let verdict = answers::check(&run, Some(expected));
+
+        checks.push((run, verdict));
+    }
+
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_discover_examples() {
+        let examples = discover(Path::new(ed_2022::DATA_DIR)).unwrap();
+
+        let day9: Vec<&str> = examples
+            .iter()
+            .filter(|e| e.day == 9)
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(day9, ["example", "larger"]);
+        assert!(examples.windows(2).all(|w| w[0].day <= w[1].day));
+    }
+
+    #[test]
+    fn it_should_check_examples() {
+        let examples = discover(Path::new(ed_2022::DATA_DIR)).unwrap();
+        let example = examples.iter().find(|e| e.day == 5).unwrap();
+        let mut solution = ed_2022::get_solution(5).unwrap();
+
+        let checks = check(example, solution.as_mut()).unwrap();
+
+        assert_eq!(example.expected(1), Some("CMZ".to_owned()));
+        assert_eq!(checks.len(), 2);
+        assert!(checks.iter().all(|(_, v)| *v == Verdict::Pass));
+    }
+}
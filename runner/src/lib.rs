@@ -0,0 +1,46 @@
+pub mod answers;
+pub mod bench;
+pub mod client;
+pub mod config;
+pub mod examples;
+pub mod repl;
+pub mod report;
+pub mod run;
+pub mod scaffold;
+pub mod submit;
+pub mod watch;
+
+use aoc::Event;
+
+/// Every event with solutions, ordered by year
+pub fn events() -> Vec<Event> {
+    vec![ed_2022::event()]
+}
+
+/// Event of the given year, if there are solutions for it
+pub fn get_event(year: u16) -> Option<Event> {
+    events().into_iter().find(|e| e.year == year)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::Answer;
+
+    #[test]
+    fn it_should_register_events_in_year_order() {
+        let years: Vec<u16> = events().iter().map(|e| e.year).collect();
+
+        assert!(years.windows(2).all(|w| w[0] < w[1]));
+        assert!(years.contains(&2022));
+    }
+
+    #[test]
+    fn it_should_dispatch_by_year_and_day() {
+        let mut solution = get_event(2022).unwrap().get_solution(4).unwrap();
+        solution.parse("2-4,6-8\n2-8,3-7\n6-6,4-6").unwrap();
+
+        assert_eq!(solution.solve(1), Ok(Answer::Number(2)));
+        assert!(get_event(2015).is_none());
+    }
+}
@@ -0,0 +1,776 @@
+use aoc::Event;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use runner::answers::{self, Answers, Verdict};
+use runner::bench::{self, Baseline, DayBench};
+use runner::client::{Client, Fetched, Throttle};
+use runner::config::{self, Config};
+use runner::repl;
+use runner::report;
+use runner::run::{self, InputSource, PartRun, RunError};
+use runner::scaffold;
+use runner::submit::{self, Outcome, Submission, SubmissionLog};
+use runner::watch::{self, Watcher};
+use std::collections::HashMap;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+/// Advent of code solutions
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    subcommand_negates_reqs = true
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Year of the event, the latest one by default
+    #[arg(short, long, global = true, env = "AOC_YEAR")]
+    year: Option<u16>,
+
+    /// AOC day to run
+    #[arg(short, long, required = true)]
+    day: Option<u8>,
+
+    /// Part of the problem
+    #[arg(short, long, required = true)]
+    part: Option<u8>,
+
+    /// Read the input from this file instead of the data directory, `-` for stdin
+    #[arg(short, long, conflicts_with = "input_text")]
+    input: Option<String>,
+
+    /// Use this text as the puzzle input
+    #[arg(long)]
+    input_text: Option<String>,
+
+    /// Directory with the `dayN_input.txt` files, the data directory of the year by default
+    #[arg(long, global = true, env = "AOC_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Output format of the run, text by default
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run every day and part, printing a summary table
+    All {
+        /// Output format of the runs
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Check every day and part against the recorded answers
+    Verify {
+        /// Answers file, `answers.toml` in the data directory by default
+        #[arg(long)]
+        answers: Option<PathBuf>,
+    },
+    /// Time parsing and solving every day and part
+    Bench(BenchArgs),
+    /// Create the module, input and example files of a new day
+    New {
+        /// Day to create
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Download the puzzle input of a day into the data directory, unless it is already there
+    Fetch {
+        /// Day to download
+        #[arg(short, long)]
+        day: u8,
+
+        #[command(flatten)]
+        client: ClientArgs,
+    },
+    /// Submit the answer of a part, unless previous submissions show it is wrong
+    Submit(SubmitArgs),
+    /// Rerun the tests and the solution of a day whenever its module, input or examples change
+    Watch {
+        /// Day to watch
+        #[arg(short, long)]
+        day: u8,
+
+        /// Only solve this part
+        #[arg(short, long)]
+        part: Option<u8>,
+
+        /// Milliseconds between two checks for changes
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+    /// Solve days interactively from pasted inputs, changing their parameters along the way
+    Repl,
+    /// Print a random puzzle input of a day, the same for the same size and seed
+    Generate {
+        /// Day of the input
+        #[arg(short, long)]
+        day: u8,
+
+        /// Number of lines or items of the input, roughly
+        #[arg(short, long, default_value_t = 1000)]
+        size: usize,
+
+        /// Seed of the random generator
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct SubmitArgs {
+    /// Day of the answer
+    #[arg(short, long)]
+    day: u8,
+
+    /// Part of the answer
+    #[arg(short, long)]
+    part: u8,
+
+    /// Answer to submit, solved from the input of the day by default
+    answer: Option<String>,
+
+    #[command(flatten)]
+    client: ClientArgs,
+}
+
+/// Settings to talk to the advent of code website, overriding the ones of the config file
+#[derive(clap::Args, Debug)]
+struct ClientArgs {
+    /// Value of the `session` cookie of a logged in user
+    #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+    session: Option<String>,
+
+    /// Address of the website
+    #[arg(long, env = "AOC_BASE_URL")]
+    base_url: Option<String>,
+
+    /// Config file, `~/.config/aoc/config.toml` by default
+    #[arg(long, env = "AOC_CONFIG")]
+    config: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct BenchArgs {
+    /// Only benchmark this day
+    #[arg(short, long)]
+    day: Option<u8>,
+
+    /// Timed runs per day
+    #[arg(long, default_value_t = 10)]
+    runs: usize,
+
+    /// Untimed runs before the timed ones
+    #[arg(long, default_value_t = 3)]
+    warmup: usize,
+
+    /// Save the medians to this file to compare later runs against them
+    #[arg(long)]
+    save_baseline: Option<PathBuf>,
+
+    /// Compare the medians against a saved baseline
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Slowdown over the baseline, in percent, reported as a regression
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    if let Some(conflict) = single_day_conflict(&args) {
+        Args::command()
+            .error(ErrorKind::ArgumentConflict, conflict)
+            .exit();
+    }
+
+    let event = match args.year {
+        Some(year) => runner::get_event(year),
+        None => runner::events().pop(),
+    };
+    let Some(event) = event else {
+        eprintln!("There are no solutions for that year");
+        return ExitCode::FAILURE;
+    };
+
+    let data_dir = args.data_dir.unwrap_or_else(|| event.data_dir.to_owned());
+    let source = match (args.input, args.input_text) {
+        (Some(input), _) => InputSource::from_arg(&input),
+        (None, Some(text)) => InputSource::Text(text),
+        (None, None) => InputSource::DataDir(data_dir.to_owned()),
+    };
+
+    match args.command {
+        Some(Command::All { format }) => run_all(&event, &source, format),
+        Some(Command::Verify { answers }) => {
+            let answers = answers.unwrap_or_else(|| answers::answers_path(&data_dir));
+            verify_all(&event, &source, &answers)
+        }
+        Some(Command::Bench(bench_args)) => bench_all(&event, &source, &bench_args),
+        Some(Command::New { day }) => new_day(&event, &data_dir, day),
+        Some(Command::Fetch { day, client }) => fetch(&event, &data_dir, day, &client),
+        Some(Command::Submit(submit_args)) => submit(&event, &source, &data_dir, &submit_args),
+        Some(Command::Watch {
+            day,
+            part,
+            interval,
+        }) => watch_day(
+            &event,
+            &data_dir,
+            day,
+            part,
+            Duration::from_millis(interval),
+        ),
+        Some(Command::Repl) => repl(&event),
+        Some(Command::Generate { day, size, seed }) => generate(&event, day, size, seed),
+        None => run_one(
+            &event,
+            args.day.unwrap(),
+            args.part.unwrap(),
+            &source,
+            args.format.unwrap_or(Format::Text),
+        ),
+    }
+}
+
+/// Arguments of a single day run given along with a subcommand, which would ignore them
+fn single_day_conflict(args: &Args) -> Option<&'static str> {
+    args.command.as_ref()?;
+
+    if args.day.is_some() || args.part.is_some() {
+        Some("a day or part can only be given when running a single day, or after the commands taking them")
+    } else if args.input.is_some() || args.input_text.is_some() {
+        Some("an input can only be given when running a single day")
+    } else if args.format.is_some() {
+        Some("an output format can only be given when running a single day, or after `all`")
+    } else {
+        None
+    }
+}
+
+fn run_one(event: &Event, day: u8, part: u8, source: &InputSource, format: Format) -> ExitCode {
+    let Some(mut solution) = event.get_solution(day) else {
+        println!("There are no problems for that day");
+        return ExitCode::SUCCESS;
+    };
+
+    let run = match source.read(day) {
+        Ok(contents) => run::run_part(solution.as_mut(), &contents, part),
+        Err(err) if format == Format::Text => {
+            eprintln!("Could not read the input of day {}: {}", day, err);
+            return ExitCode::FAILURE;
+        }
+        Err(err) => PartRun {
+            day,
+            part,
+            answer: Err(RunError::Input(err.to_string())),
+            elapsed: Duration::ZERO,
+        },
+    };
+
+    match format {
+        Format::Text => match &run.answer {
+            Ok(answer) => println!("{}", answer),
+            Err(RunError::Solution(err)) => eprintln!("{}", err.diagnostic()),
+            Err(err) => eprintln!("{}", err),
+        },
+        Format::Json => println!("{}", report::to_json(&run)),
+        Format::Csv => print!("{}", report::to_csv(std::slice::from_ref(&run))),
+    }
+
+    if run.answer.is_ok() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn run_all(event: &Event, source: &InputSource, format: Format) -> ExitCode {
+    let runs = run_every_part(event, source);
+    let failed = runs.iter().filter(|r| r.answer.is_err()).count();
+
+    match format {
+        Format::Text => {
+            print_table(&runs);
+
+            let total: Duration = runs.iter().map(|r| r.elapsed).sum();
+            println!();
+            println!(
+                "{} parts run, {} failed in {}",
+                runs.len(),
+                failed,
+                report::format_duration(total)
+            );
+        }
+        Format::Json => println!("{}", report::to_json_array(&runs)),
+        Format::Csv => print!("{}", report::to_csv(&runs)),
+    }
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn verify_all(event: &Event, source: &InputSource, answers_path: &Path) -> ExitCode {
+    let answers = match Answers::load(answers_path) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("Could not read {}: {}", answers_path.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for run in run_every_part(event, source) {
+        let label = format!("day {:>2} part {}", run.day, run.part);
+
+        match answers::verify(&run, &answers) {
+            Verdict::Pass => {
+                passed += 1;
+                println!("pass     {}", label);
+            }
+            Verdict::Missing => {
+                missing += 1;
+                println!("missing  {}", label);
+            }
+            Verdict::Error(err) => {
+                failed += 1;
+                println!("error    {}: {}", label, err);
+            }
+            Verdict::Fail { expected, actual } => {
+                failed += 1;
+                println!("fail     {}", label);
+                for line in answers::diff(&expected, &actual).lines() {
+                    println!("           {}", line);
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn bench_all(event: &Event, source: &InputSource, args: &BenchArgs) -> ExitCode {
+    let baseline = match args.baseline.as_deref().map(Baseline::load).transpose() {
+        Ok(baseline) => baseline,
+        Err(err) => {
+            eprintln!("Could not read the baseline: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut benches: Vec<DayBench> = vec![];
+    let mut failed = false;
+    println!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "Day", "Phase", "Min", "Median", "Mean", "Stddev"
+    );
+    for mut solution in event.solutions() {
+        if args.day.is_some_and(|d| d != solution.day()) {
+            continue;
+        }
+
+        let result = source
+            .read(solution.day())
+            .map_err(|err| format!("could not read input: {}", err))
+            .and_then(|contents| {
+                bench::bench_day(solution.as_mut(), &contents, args.runs, args.warmup)
+                    .map_err(|err| err.to_string())
+            });
+
+        match result {
+            Ok(bench) => {
+                for (phase, stats) in &bench.phases {
+                    println!(
+                        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>10}",
+                        bench.day,
+                        phase.to_string(),
+                        report::format_duration(stats.min),
+                        report::format_duration(stats.median),
+                        report::format_duration(stats.mean),
+                        report::format_duration(stats.stddev)
+                    );
+                }
+                benches.push(bench);
+            }
+            Err(err) => {
+                failed = true;
+                println!("{:>3}  error: {}", solution.day(), err);
+            }
+        }
+    }
+
+    if let Some(path) = &args.save_baseline {
+        if let Err(err) = Baseline::from_benches(&benches).save(path) {
+            eprintln!("Could not save the baseline: {}", err);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if let Some(baseline) = baseline {
+        let comparisons = bench::compare(&benches, &baseline);
+
+        println!();
+        println!(
+            "{:>3}  {:<6}  {:>10}  {:>10}  {:>8}",
+            "Day", "Phase", "Baseline", "Median", "Change"
+        );
+        for comparison in &comparisons {
+            let regression = comparison.is_regression(args.threshold);
+            failed |= regression;
+            println!(
+                "{:>3}  {:<6}  {:>10}  {:>10}  {:>+7.1}%{}",
+                comparison.day,
+                comparison.phase.to_string(),
+                report::format_duration(comparison.baseline),
+                report::format_duration(comparison.current),
+                comparison.change,
+                if regression { "  regression" } else { "" }
+            );
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn new_day(event: &Event, data_dir: &Path, day: u8) -> ExitCode {
+    match scaffold::new_day(&event.crate_dir, data_dir, day) {
+        Ok(paths) => {
+            for path in paths {
+                println!("{}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Could not create day {}: {}", day, err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn repl(event: &Event) -> ExitCode {
+    match repl::run_session(event, io::stdin().lock(), io::stdout()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Could not read the commands: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn generate(event: &Event, day: u8, size: usize, seed: u64) -> ExitCode {
+    match event.generate(day, size, seed) {
+        Some(data) => {
+            print!("{}", data);
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("Cannot generate inputs for day {} of {}", day, event.year);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn fetch(event: &Event, data_dir: &Path, day: u8, args: &ClientArgs) -> ExitCode {
+    let client = match client(args) {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match client.fetch_input(event.year, day, data_dir) {
+        Ok(Fetched::Cached(path)) => {
+            println!("{} already exists", path.display());
+            ExitCode::SUCCESS
+        }
+        Ok(Fetched::Downloaded(path)) => {
+            println!("{}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Could not fetch the input of day {}: {}", day, err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn submit(event: &Event, source: &InputSource, data_dir: &Path, args: &SubmitArgs) -> ExitCode {
+    let answer = match &args.answer {
+        Some(answer) => answer.to_owned(),
+        None => match solve(event, source, args.day, args.part) {
+            Ok(answer) => answer,
+            Err(err) => {
+                eprintln!(
+                    "Could not solve day {} part {}: {}",
+                    args.day, args.part, err
+                );
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    let log_path = submit::submissions_path(data_dir);
+    let mut log = match SubmissionLog::load(&log_path) {
+        Ok(log) => log,
+        Err(err) => {
+            eprintln!("Could not read {}: {}", log_path.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(refusal) = log.check(event.year, args.day, args.part, &answer) {
+        eprintln!("Not submitting {}: {}", answer, refusal);
+        return ExitCode::FAILURE;
+    }
+
+    let outcome = match client(&args.client).and_then(|c| {
+        c.submit(event.year, args.day, args.part, &answer)
+            .map_err(|e| e.to_string())
+    }) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            eprintln!("Could not submit {}: {}", answer, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let submission = Submission::new(event.year, args.day, args.part, &answer, outcome);
+    if let Err(err) = log.record(submission) {
+        eprintln!(
+            "Could not record the submission in {}: {}",
+            log_path.display(),
+            err
+        );
+    }
+    println!("{}: {}", answer, outcome);
+
+    if outcome == Outcome::Right {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn watch_day(
+    event: &Event,
+    data_dir: &Path,
+    day: u8,
+    part: Option<u8>,
+    interval: Duration,
+) -> ExitCode {
+    let parts: Vec<u8> = part.map_or(aoc::PARTS.to_vec(), |p| vec![p]);
+    let mut watcher = Watcher::new(watch::day_paths(&event.crate_dir, data_dir, day));
+    let mut previous: HashMap<u8, String> = HashMap::new();
+
+    loop {
+        match watch::run_tests(&event.crate_dir, day) {
+            Ok(true) => println!("tests of day {} passed", day),
+            Ok(false) => println!("tests of day {} failed", day),
+            Err(err) => {
+                eprintln!("Could not run cargo: {}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+
+        for part in &parts {
+            let answer = match watch::solve(&event.crate_dir, event.year, day, *part, data_dir) {
+                Ok(Ok(answer)) => answer,
+                Ok(Err(err)) => {
+                    println!("part {} failed:\n{}", part, err.trim_end());
+                    continue;
+                }
+                Err(err) => {
+                    eprintln!("Could not run cargo: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            match previous.get(part) {
+                Some(last) if *last != answer => {
+                    println!("part {} changed:", part);
+                    for line in answers::diff(last, &answer).lines() {
+                        println!("  {}", line);
+                    }
+                }
+                Some(_) => println!("part {} unchanged: {}", part, answer.trim_end()),
+                None => println!("part {}: {}", part, answer.trim_end()),
+            }
+            previous.insert(*part, answer);
+        }
+
+        println!("watching day {} for changes...", day);
+        loop {
+            thread::sleep(interval);
+
+            let changed = watcher.changed();
+            if !changed.is_empty() {
+                println!();
+                for path in changed {
+                    println!("changed {}", path.display());
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// Answer of a part solved from its input, if it fits on a line
+fn solve(event: &Event, source: &InputSource, day: u8, part: u8) -> Result<String, String> {
+    let mut solution = event
+        .get_solution(day)
+        .ok_or_else(|| "there is no solution for that day".to_owned())?;
+    let contents = source.read(day).map_err(|err| err.to_string())?;
+
+    let answer = run::run_part(solution.as_mut(), &contents, part)
+        .answer
+        .map_err(|err| err.to_string())?
+        .to_string();
+    if answer.trim().contains('\n') {
+        return Err("the answer spans several lines, give it as an argument".to_owned());
+    }
+
+    Ok(answer.trim().to_owned())
+}
+
+/// Client of the website, with the settings of the arguments taking precedence over the config
+fn client(args: &ClientArgs) -> Result<Client, String> {
+    let config = match args.config.to_owned().or_else(config::default_config_path) {
+        Some(path) => Config::load(&path)
+            .map_err(|err| format!("Could not read {}: {}", path.display(), err))?,
+        None => Config::default(),
+    };
+
+    let Some(session) = args.session.as_deref().or(config.session.as_deref()) else {
+        return Err(
+            "No session cookie, set AOC_SESSION or `session` in the config file".to_owned(),
+        );
+    };
+    let base_url = args.base_url.as_deref().unwrap_or(config.base_url());
+    let throttle = Throttle::new(
+        std::env::temp_dir().join("aoc-last-request"),
+        config.throttle(),
+    );
+
+    Ok(Client::new(base_url, session, throttle))
+}
+
+/// Runs both parts of every day, reporting unreadable inputs as failed runs
+fn run_every_part(event: &Event, source: &InputSource) -> Vec<PartRun> {
+    // Panics are reported along with the other errors, keep the default hook from printing them
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut runs: Vec<PartRun> = vec![];
+    for mut solution in event.solutions() {
+        let day = solution.day();
+        let contents = source.read(day);
+
+        for part in aoc::PARTS {
+            match &contents {
+                Ok(contents) => runs.push(run::run_part(solution.as_mut(), contents, part)),
+                Err(err) => runs.push(PartRun {
+                    day,
+                    part,
+                    answer: Err(RunError::Input(err.to_string())),
+                    elapsed: Duration::ZERO,
+                }),
+            }
+        }
+    }
+    panic::set_hook(hook);
+
+    runs
+}
+
+fn print_table(runs: &[PartRun]) {
+    let answers: Vec<String> = runs
+        .iter()
+        .map(|r| match &r.answer {
+            Ok(answer) => answer.to_string(),
+            Err(err) => format!("error: {}", err),
+        })
+        .collect();
+    let width = answers
+        .iter()
+        .flat_map(|a| a.lines())
+        .map(|l| l.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Answer".len());
+
+    println!(
+        "{:>3}  {:>4}  {:<width$}  {:>10}",
+        "Day", "Part", "Answer", "Time"
+    );
+    for (run, answer) in runs.iter().zip(answers) {
+        let mut lines = answer.lines();
+        println!(
+            "{:>3}  {:>4}  {:<width$}  {:>10}",
+            run.day,
+            run.part,
+            lines.next().unwrap_or(""),
+            report::format_duration(run.elapsed)
+        );
+        for line in lines {
+            println!("{:>3}  {:>4}  {}", "", "", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_only_create_days_of_the_calendar() {
+        for day in ["0", "26"] {
+            let result = Args::try_parse_from(["aoc", "new", "--day", day]);
+
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+        }
+        assert!(Args::try_parse_from(["aoc", "new", "--day", "25"]).is_ok());
+    }
+
+    #[test]
+    fn it_should_reject_single_day_arguments_before_commands() {
+        let conflict = |args: &[&str]| single_day_conflict(&Args::try_parse_from(args).unwrap());
+
+        assert!(conflict(&["aoc", "--day", "3", "bench"]).is_some());
+        assert!(conflict(&["aoc", "--part", "1", "all"]).is_some());
+        assert!(conflict(&["aoc", "--format", "json", "all"]).is_some());
+        assert_eq!(conflict(&["aoc", "bench", "--day", "3"]), None);
+        assert_eq!(conflict(&["aoc", "--day", "3", "--part", "1"]), None);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::report;
+use crate::run::{self, RunError};
+use aoc::{Event, Solution, PARTS};
+use std::io::{self, BufRead, Write};
+
+/// Commands of a session, as shown by `help`
+const HELP: &str = "Commands:
+  day N           select the day to run, resetting its parameters
+  input           paste an input, ended by a `.end` line or the end of the session
+  run [PART]      solve both parts, or only the given one, from the pasted input
+  params          list the parameters of the day with their values
+  set NAME VALUE  change a parameter of the day
+  help            show this help
+  quit            leave the session";
+
+/// Reply of a session to a line typed by the user
+#[derive(Debug, PartialEq)]
+pub enum Reply {
+    /// Text to show, empty when there is nothing to show
+    Text(String),
+    Quit,
+}
+
+/// Interactive session solving the days of an event from pasted inputs
+pub struct Session<'a> {
+    event: &'a Event,
+    solution: Option<Box<dyn Solution>>,
+    input: Option<String>,
+    /// Lines of the input being pasted
+    pasting: Option<Vec<String>>,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(event: &'a Event) -> Session<'a> {
+        Session {
+            event,
+            solution: None,
+            input: None,
+            pasting: None,
+        }
+    }
+
+    /// Prompt to show before reading the next line
+    pub fn prompt(&self) -> String {
+        match (&self.pasting, &self.solution) {
+            (Some(_), _) => String::new(),
+            (None, Some(solution)) => format!("day {}> ", solution.day()),
+            (None, None) => "> ".to_owned(),
+        }
+    }
+
+    /// Handles a line typed by the user, either a command or a line of the input being pasted
+    pub fn handle(&mut self, line: &str) -> Reply {
+        if self.pasting.is_some() {
+            return Reply::Text(self.paste(line));
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let text = match words.as_slice() {
+            [] => String::new(),
+            ["help"] => HELP.to_owned(),
+            ["quit"] | ["exit"] => return Reply::Quit,
+            ["day", day] => self.select(day),
+            ["input"] => {
+                self.pasting = Some(vec![]);
+                "Paste the input, then a `.end` line".to_owned()
+            }
+            ["run"] => self.run(&PARTS),
+            ["run", part] => match part.parse() {
+                Ok(part) => self.run(&[part]),
+                Err(_) => format!("Invalid part `{}`", part),
+            },
+            ["params"] => self.params(),
+            ["set", name, value] => self.set(name, value),
+            _ => format!(
+                "Unknown command `{}`, type `help` to list the commands",
+                line.trim()
+            ),
+        };
+
+        Reply::Text(text)
+    }
+
+    /// Keeps the input being pasted, which may have blank lines, until its `.end` line
+    fn paste(&mut self, line: &str) -> String {
+        if line.trim() == ".end" {
+            return self.finish_paste().unwrap_or_default();
+        }
+
+        self.pasting
+            .get_or_insert_with(Vec::new)
+            .push(line.to_owned());
+        String::new()
+    }
+
+    /// Takes the input being pasted as the one to solve, when there is one
+    pub fn finish_paste(&mut self) -> Option<String> {
+        let lines = self.pasting.take()?;
+        self.input = Some(lines.join("\n") + "\n");
+
+        Some(format!("Read {} lines", lines.len()))
+    }
+
+    fn select(&mut self, day: &str) -> String {
+        match day
+            .parse()
+            .ok()
+            .and_then(|day| self.event.get_solution(day))
+        {
+            Some(solution) => {
+                let text = format!("Day {} selected", solution.day());
+                self.solution = Some(solution);
+                text
+            }
+            None => format!("There are no solutions for day {}", day),
+        }
+    }
+
+    fn run(&mut self, parts: &[u8]) -> String {
+        let (Some(solution), Some(input)) = (&mut self.solution, &self.input) else {
+            return "Select a day and paste an input first".to_owned();
+        };
+
+        let results: Vec<String> = parts
+            .iter()
+            .map(|part| {
+                let run = run::run_part(solution.as_mut(), input, *part);
+                let answer = match &run.answer {
+                    Ok(answer) => answer.to_string(),
+                    Err(RunError::Solution(err)) => err.diagnostic(),
+                    Err(err) => err.to_string(),
+                };
+                let label = format!("part {} ({})", part, report::format_duration(run.elapsed));
+
+                if answer.contains('\n') {
+                    format!("{}:\n{}", label, answer)
+                } else {
+                    format!("{}: {}", label, answer)
+                }
+            })
+            .collect();
+
+        results.join("\n")
+    }
+
+    fn params(&self) -> String {
+        let Some(solution) = &self.solution else {
+            return "Select a day first".to_owned();
+        };
+
+        let params: Vec<String> = solution
+            .params()
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect();
+        if params.is_empty() {
+            format!("Day {} has no parameters", solution.day())
+        } else {
+            params.join("\n")
+        }
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> String {
+        let Some(solution) = &mut self.solution else {
+            return "Select a day first".to_owned();
+        };
+
+        match solution.set_param(name, value) {
+            Ok(()) => format!("{} = {}", name, value),
+            Err(err) => err.to_string(),
+        }
+    }
+}
+
+/// Runs a session on the lines read from `input` until `quit` or the end of the input
+pub fn run_session(event: &Event, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut session = Session::new(event);
+    let mut lines = input.lines();
+    writeln!(output, "Type `help` to list the commands")?;
+
+    loop {
+        write!(output, "{}", session.prompt())?;
+        output.flush()?;
+
+        let Some(line) = lines.next() else {
+            if let Some(text) = session.finish_paste() {
+                writeln!(output, "{}", text)?;
+            }
+            return Ok(());
+        };
+        match session.handle(&line?) {
+            Reply::Text(text) if text.is_empty() => {}
+            Reply::Text(text) => writeln!(output, "{}", text)?,
+            Reply::Quit => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_data() -> &'static str {
+        "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2\n"
+    }
+
+    fn text(reply: Reply) -> String {
+        match reply {
+            Reply::Text(text) => text,
+            Reply::Quit => panic!("the session ended"),
+        }
+    }
+
+    #[test]
+    fn it_should_solve_pasted_inputs_with_parameters() {
+        let event = ed_2022::event();
+        let mut session = Session::new(&event);
+
+        assert_eq!(
+            text(session.handle("run")),
+            "Select a day and paste an input first"
+        );
+        assert_eq!(text(session.handle("day 9")), "Day 9 selected");
+        session.handle("input");
+        for line in get_data().lines().chain([".end"]) {
+            session.handle(line);
+        }
+
+        assert!(text(session.handle("run 2")).ends_with(": 1"));
+        assert_eq!(text(session.handle("set part2_knots 2")), "part2_knots = 2");
+        assert!(text(session.handle("run 2")).ends_with(": 13"));
+        assert!(text(session.handle("set knots 2")).contains("no parameter `knots`"));
+        assert_eq!(session.prompt(), "day 9> ");
+        assert_eq!(session.handle("quit"), Reply::Quit);
+    }
+
+    #[test]
+    fn it_should_reject_parameters_out_of_range() {
+        let event = ed_2022::event();
+        let mut session = Session::new(&event);
+
+        session.handle("day 9");
+        let knots = text(session.handle("set part2_knots 9223372036854775807"));
+        session.handle("day 11");
+        let rounds = text(session.handle("set part2_rounds 18446744073709551615"));
+
+        assert!(knots.contains("expected 1 to 1000"), "{}", knots);
+        assert!(rounds.contains("expected 1 to 100000"), "{}", rounds);
+        assert_eq!(
+            text(session.handle("params")).lines().nth(1),
+            Some("part2_rounds = 10000")
+        );
+    }
+
+    #[test]
+    fn it_should_run_sessions_until_quit() {
+        let event = ed_2022::event();
+        let commands = format!("day 9\ninput\n{}.end\nrun\nquit\nrun\n", get_data());
+        let mut output: Vec<u8> = vec![];
+
+        run_session(&event, commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("Read 8 lines"));
+        assert!(output.contains(": 13\n"));
+        assert_eq!(output.matches("part ").count(), 2);
+    }
+
+    #[test]
+    fn it_should_keep_blank_lines_of_pasted_inputs() {
+        let event = ed_2022::event();
+        let commands = "day 1\ninput\n1000\n\n\n2000\n3000\n.end\nrun 1\ninput\n4000\n\n\n";
+        let mut output: Vec<u8> = vec![];
+
+        run_session(&event, commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("Read 5 lines"), "{}", output);
+        assert!(output.contains(": 5000\n"), "{}", output);
+        assert!(output.ends_with("Read 3 lines\n"), "{}", output);
+    }
+}
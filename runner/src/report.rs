@@ -0,0 +1,209 @@
+use crate::run::{PartRun, RunError};
+use aoc::Answer;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Run of a part as reported to other tools
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    /// `number` or `text`
+    pub answer_type: Option<&'static str>,
+    pub elapsed_ns: u64,
+    pub error: Option<ErrorRecord>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ErrorRecord {
+    /// `input`, `solution` or `panic`
+    pub kind: &'static str,
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl From<&PartRun> for Record {
+    fn from(run: &PartRun) -> Self {
+        let (answer, answer_type, error) = match &run.answer {
+            Ok(answer @ Answer::Number(_)) => (Some(answer.to_string()), Some("number"), None),
+            Ok(answer @ Answer::Text(_)) => (Some(answer.to_string()), Some("text"), None),
+            Err(err) => (None, None, Some(ErrorRecord::from(err))),
+        };
+
+        Record {
+            day: run.day,
+            part: run.part,
+            answer,
+            answer_type,
+            elapsed_ns: run.elapsed.as_nanos() as u64,
+            error,
+        }
+    }
+}
+
+impl From<&RunError> for ErrorRecord {
+    fn from(err: &RunError) -> Self {
+        match err {
+            RunError::Input(message) => ErrorRecord {
+                kind: "input",
+                message: message.to_owned(),
+                line: None,
+                column: None,
+            },
+            RunError::Solution(err) => ErrorRecord {
+                kind: "solution",
+                message: err.message.to_owned(),
+                line: err.line,
+                column: err.column,
+            },
+            RunError::Panic(message) => ErrorRecord {
+                kind: "panic",
+                message: message.to_owned(),
+                line: None,
+                column: None,
+            },
+        }
+    }
+}
+
+/// JSON object describing a run
+pub fn to_json(run: &PartRun) -> String {
+    serde_json::to_string_pretty(&Record::from(run)).unwrap()
+}
+
+/// JSON array describing several runs
+pub fn to_json_array(runs: &[PartRun]) -> String {
+    let records: Vec<Record> = runs.iter().map(Record::from).collect();
+
+    serde_json::to_string_pretty(&records).unwrap()
+}
+
+/// CSV table with a header and a row per run
+pub fn to_csv(runs: &[PartRun]) -> String {
+    let mut csv =
+        "day,part,answer,answer_type,elapsed_ns,error_kind,error_message,error_line,error_column\n"
+            .to_owned();
+
+    for record in runs.iter().map(Record::from) {
+        let error = record.error.as_ref();
+        let fields = [
+            record.day.to_string(),
+            record.part.to_string(),
+            record.answer.unwrap_or_default(),
+            record.answer_type.unwrap_or_default().to_owned(),
+            record.elapsed_ns.to_string(),
+            error.map(|e| e.kind.to_owned()).unwrap_or_default(),
+            error.map(|e| e.message.to_owned()).unwrap_or_default(),
+            optional(error.and_then(|e| e.line)),
+            optional(error.and_then(|e| e.column)),
+        ];
+
+        csv += &fields.map(|f| csv_field(&f)).join(",");
+        csv += "\n";
+    }
+
+    csv
+}
+
+/// Duration with a unit suited to its magnitude, like `1.5µs` or `2.31ms`
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1_000_000.0;
+
+    if micros < 1_000.0 {
+        format!("{:.1}µs", micros)
+    } else if micros < 1_000_000.0 {
+        format!("{:.2}ms", micros / 1_000.0)
+    } else {
+        format!("{:.2}s", micros / 1_000_000.0)
+    }
+}
+
+fn optional(value: Option<usize>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::Error;
+
+    fn get_runs() -> Vec<PartRun> {
+        vec![
+            PartRun {
+                day: 10,
+                part: 2,
+                answer: Ok(Answer::Text("#.\n.#\n".to_owned())),
+                elapsed: Duration::from_micros(15),
+            },
+            PartRun {
+                day: 5,
+                part: 1,
+                answer: Err(RunError::Solution(Error::at(
+                    5,
+                    4,
+                    "move x from 1 to 2",
+                    1,
+                    "expected `move N from X to Y`",
+                ))),
+                elapsed: Duration::from_nanos(300),
+            },
+        ]
+    }
+
+    #[test]
+    fn it_should_describe_runs_as_json() {
+        let runs = get_runs();
+
+        let result: serde_json::Value = serde_json::from_str(&to_json_array(&runs)).unwrap();
+
+        assert_eq!(
+            result,
+            serde_json::json!([
+                {
+                    "day": 10,
+                    "part": 2,
+                    "answer": "#.\n.#\n",
+                    "answer_type": "text",
+                    "elapsed_ns": 15000,
+                    "error": null
+                },
+                {
+                    "day": 5,
+                    "part": 1,
+                    "answer": null,
+                    "answer_type": null,
+                    "elapsed_ns": 300,
+                    "error": {
+                        "kind": "solution",
+                        "message": "expected `move N from X to Y`",
+                        "line": 4,
+                        "column": 1
+                    }
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn it_should_describe_runs_as_csv() {
+        let result = to_csv(&get_runs());
+
+        assert_eq!(
+            result,
+            "day,part,answer,answer_type,elapsed_ns,error_kind,error_message,error_line,error_column
+10,2,\"#.\n.#\n\",text,15000,,,,
+5,1,,,300,solution,expected `move N from X to Y`,4,1
+"
+        );
+    }
+}
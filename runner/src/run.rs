@@ -0,0 +1,186 @@
+use aoc::{Answer, Error, Solution};
+use std::any::Any;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Where the puzzle input of a day is read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    /// `dayN_input.txt` inside the given data directory
+    DataDir(PathBuf),
+    File(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl InputSource {
+    /// Source for a `--input` argument, where `-` stands for stdin
+    pub fn from_arg(arg: &str) -> InputSource {
+        if arg == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn read(&self, day: u8) -> io::Result<String> {
+        match self {
+            InputSource::DataDir(dir) => fs::read_to_string(input_path(dir, day)),
+            InputSource::File(path) => fs::read_to_string(path),
+            InputSource::Stdin => {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents)?;
+                Ok(contents)
+            }
+            InputSource::Text(text) => Ok(text.to_owned()),
+        }
+    }
+}
+
+/// Path of the input file of a day inside a data directory
+pub fn input_path(data_dir: &Path, day: u8) -> PathBuf {
+    data_dir.join(format!("day{}_input.txt", day))
+}
+
+/// Reason a part could not be solved
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunError {
+    /// The input could not be read
+    Input(String),
+    /// The solution rejected the input
+    Solution(Error),
+    Panic(String),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Input(message) => write!(f, "could not read input: {}", message),
+            RunError::Solution(err) => write!(f, "{}", err),
+            RunError::Panic(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+/// Outcome of running one part of a day
+#[derive(Debug)]
+pub struct PartRun {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Result<Answer, RunError>,
+    pub elapsed: Duration,
+}
+
+/// Parses `data` and solves `part`, catching any panic along the way
+pub fn run_part(solution: &mut dyn Solution, data: &str, part: u8) -> PartRun {
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        solution.parse(data)?;
+        solution.solve(part)
+    }));
+    let elapsed = start.elapsed();
+
+    let answer = match result {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(err)) => Err(RunError::Solution(err)),
+        Err(payload) => Err(RunError::Panic(panic_message(payload.as_ref()))),
+    };
+
+    PartRun {
+        day: solution.day(),
+        part,
+        answer,
+        elapsed,
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.to_owned()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed_2022::get_solution;
+
+    #[test]
+    fn it_should_read_input_from_data_dir() {
+        let source = InputSource::DataDir(PathBuf::from(ed_2022::DATA_DIR));
+
+        let contents = source.read(6).unwrap();
+
+        assert!(!contents.is_empty());
+    }
+
+    #[test]
+    fn it_should_parse_input_arg() {
+        assert_eq!(InputSource::from_arg("-"), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg("example.txt"),
+            InputSource::File(PathBuf::from("example.txt"))
+        );
+    }
+
+    #[test]
+    fn it_should_run_a_part() {
+        let mut solution = get_solution(6).unwrap();
+
+        let run = run_part(solution.as_mut(), "mjqjpqmgbljsphdztnvjfqwrcgsmlb", 2);
+
+        assert_eq!(run.day, 6);
+        assert_eq!(run.part, 2);
+        assert_eq!(run.answer, Ok(Answer::Number(19)));
+    }
+
+    #[test]
+    fn it_should_report_errors_while_running_a_part() {
+        let mut solution = get_solution(1).unwrap();
+
+        let run = run_part(solution.as_mut(), "1000\nnot a number", 1);
+
+        let Err(RunError::Solution(err)) = run.answer else {
+            panic!("expected a solution error");
+        };
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
+    }
+
+    #[test]
+    fn it_should_catch_panics_while_running_a_part() {
+        struct Panicking;
+
+        impl Solution for Panicking {
+            fn day(&self) -> u8 {
+                25
+            }
+
+            fn parse(&mut self, _data: &str) -> aoc::Result<()> {
+                Ok(())
+            }
+
+            fn part1(&self) -> aoc::Result<Answer> {
+                panic!("not solved yet")
+            }
+
+            fn part2(&self) -> aoc::Result<Answer> {
+                Ok(Answer::Number(0))
+            }
+        }
+
+        let run = run_part(&mut Panicking, "", 1);
+
+        assert_eq!(
+            run.answer,
+            Err(RunError::Panic("not solved yet".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use crate::examples;
+use crate::run;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Module of a new day, where `{day}` stands for its number
+const DAY_TEMPLATE: &str = r#"use aoc::error::Result;
+use aoc::solution::{Answer, Solution};
+
+const DAY: u8 = {day};
+
+pub fn part1(data: &str) -> Result<i64> {
+    let _lines: Vec<&str> = data.lines().collect();
+
+    todo!("solve part 1")
+}
+
+pub fn part2(data: &str) -> Result<i64> {
+    let _lines: Vec<&str> = data.lines().collect();
+
+    todo!("solve part 2")
+}
+
+#[derive(Default)]
+pub struct Day{day} {
+    data: String,
+}
+
+impl Solution for Day{day} {
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn parse(&mut self, data: &str) -> Result<()> {
+        self.data = data.to_owned();
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer> {
+        Ok(part1(&self.data)?.into())
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(part2(&self.data)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestCase {
+        input: String,
+        expected: i64,
+    }
+
+    fn get_data() -> String {
+        String::from(include_str!("../data/examples/day{day}/example.txt"))
+    }
+
+    #[test]
+    fn it_should_solve_part1() {
+        let test_case = TestCase {
+            input: get_data(),
+            expected: 0,
+        };
+
+        let result = part1(&test_case.input).unwrap();
+
+        assert_eq!(result, test_case.expected);
+    }
+
+    #[test]
+    fn it_should_solve_part2() {
+        let test_case = TestCase {
+            input: get_data(),
+            expected: 0,
+        };
+
+        let result = part2(&test_case.input).unwrap();
+
+        assert_eq!(result, test_case.expected);
+    }
+}
+"#;
+
+/// Answers file of a new example, to be filled in along with it
+const EXAMPLE_ANSWERS: &str = "# Answers of example.txt
+# part1 = 0
+# part2 = 0
+";
+
+/// Creates the module of a new day in the crate at `crate_dir`, registers it in its `lib.rs` and
+/// creates empty input and example files in `data_dir`. Nothing is written when any of the files
+/// already exists or when the day is not one of the advent calendar. Returns the paths that were
+/// created or changed.
+pub fn new_day(crate_dir: &Path, data_dir: &Path, day: u8) -> io::Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("day {} is not between 1 and 25", day),
+        ));
+    }
+
+    let lib_path = crate_dir.join("src").join("lib.rs");
+    let module_path = crate_dir.join("src").join(format!("day{}.rs", day));
+    let input_path = run::input_path(data_dir, day);
+    let example_path = examples::examples_dir(data_dir, day).join("example.txt");
+    let example_answers_path = example_path.with_extension("toml");
+
+    for path in [
+        &module_path,
+        &input_path,
+        &example_path,
+        &example_answers_path,
+    ] {
+        if path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()),
+            ));
+        }
+    }
+
+    let lib = register_day(&fs::read_to_string(&lib_path)?, day).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "day {} is already registered in {}",
+                day,
+                lib_path.display()
+            ),
+        )
+    })?;
+
+    fs::write(
+        &module_path,
+        DAY_TEMPLATE.replace("{day}", &day.to_string()),
+    )?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&input_path, "")?;
+    fs::create_dir_all(example_path.parent().unwrap())?;
+    fs::write(&example_path, "")?;
+    fs::write(&example_answers_path, EXAMPLE_ANSWERS)?;
+
+    Ok(vec![
+        module_path,
+        lib_path,
+        input_path,
+        example_path,
+        example_answers_path,
+    ])
+}
+
+/// Adds the module of a day and its solution to the contents of a `lib.rs`, keeping the modules
+/// sorted by name and the solutions by day. Returns `None` when the day is already registered.
+pub fn register_day(lib: &str, day: u8) -> Option<String> {
+    let module = format!("pub mod day{};", day);
+    let solution = format!("Box::<day{0}::Day{0}>::default(),", day);
+    let mut lines: Vec<String> = lib.lines().map(String::from).collect();
+
+    if lines.iter().any(|l| l.trim() == module) {
+        return None;
+    }
+
+    let name = format!("day{}", day);
+    let modules: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| Some((i, l.strip_prefix("pub mod ")?.trim_end_matches(';'))))
+        .collect();
+    let position = match modules.iter().find(|(_, m)| *m > name.as_str()) {
+        Some((i, _)) => *i,
+        None => modules.last().map_or(0, |(i, _)| i + 1),
+    };
+    lines.insert(position, module);
+
+    let solutions: Vec<(usize, u8)> = (0..lines.len())
+        .filter_map(|i| Some((i, registered_day(&lines[i])?)))
+        .collect();
+    let (last, _) = *solutions.last()?;
+    let indent: String = lines[last]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+    let position = solutions
+        .iter()
+        .find(|(_, d)| *d > day)
+        .map_or(last + 1, |(i, _)| *i);
+    lines.insert(position, format!("{}{}", indent, solution));
+
+    Some(lines.join("\n") + "\n")
+}
+
+/// Day of a `Box::<dayN::DayN>::default(),` line of the solutions list
+fn registered_day(line: &str) -> Option<u8> {
+    line.trim()
+        .strip_prefix("Box::<day")?
+        .split("::")
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_lib() -> String {
+        String::from(
+            "pub mod day1;
+pub mod day10;
+pub mod day2;
+
+pub fn solutions() -> Vec<Box<dyn Solution>> {
+    vec![
+        Box::<day1::Day1>::default(),
+        Box::<day2::Day2>::default(),
+        Box::<day10::Day10>::default(),
+    ]
+}
+",
+        )
+    }
+
+    #[test]
+    fn it_should_register_new_days() {
+        let result = register_day(&get_lib(), 3).unwrap();
+
+        assert_eq!(
+            result,
+            "pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day3;
+
+pub fn solutions() -> Vec<Box<dyn Solution>> {
+    vec![
+        Box::<day1::Day1>::default(),
+        Box::<day2::Day2>::default(),
+        Box::<day3::Day3>::default(),
+        Box::<day10::Day10>::default(),
+    ]
+}
+"
+        );
+        assert!(register_day(&get_lib(), 11)
+            .unwrap()
+            .contains("pub mod day10;\npub mod day11;\npub mod day2;"));
+        assert_eq!(register_day(&get_lib(), 2), None);
+    }
+
+    #[test]
+    fn it_should_not_overwrite_existing_days() {
+        let crate_dir = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let data_dir = crate_dir.join("data");
+        fs::create_dir_all(crate_dir.join("src")).unwrap();
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(crate_dir.join("src").join("lib.rs"), get_lib()).unwrap();
+
+        let created = new_day(&crate_dir, &data_dir, 3).unwrap();
+        let again = new_day(&crate_dir, &data_dir, 3).unwrap_err();
+        let registered = new_day(&crate_dir, &data_dir, 2).unwrap_err();
+
+        assert_eq!(created.len(), 5);
+        assert!(fs::read_to_string(crate_dir.join("src").join("day3.rs"))
+            .unwrap()
+            .contains("impl Solution for Day3 {"));
+        assert_eq!(again.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(registered.kind(), io::ErrorKind::AlreadyExists);
+        assert!(!crate_dir.join("src").join("day2.rs").exists());
+
+        for day in [0, 26] {
+            let outside = new_day(&crate_dir, &data_dir, day).unwrap_err();
+
+            assert_eq!(outside.kind(), io::ErrorKind::InvalidInput);
+            assert!(!crate_dir
+                .join("src")
+                .join(format!("day{}.rs", day))
+                .exists());
+        }
+        assert_eq!(
+            fs::read_to_string(crate_dir.join("src").join("lib.rs"))
+                .unwrap()
+                .matches("pub mod")
+                .count(),
+            4
+        );
+
+        fs::remove_dir_all(&crate_dir).unwrap();
+    }
+}
@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the submission log inside a data directory
+pub const SUBMISSIONS_FILE: &str = "submissions.jsonl";
+
+/// Reply of the website to a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// An answer was submitted too recently
+    RateLimited,
+    /// The part is already solved or not unlocked yet
+    WrongLevel,
+    Unknown,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Outcome::Right => "right answer",
+            Outcome::Wrong => "wrong answer",
+            Outcome::TooHigh => "wrong answer, too high",
+            Outcome::TooLow => "wrong answer, too low",
+            Outcome::RateLimited => "answer submitted too recently, wait before trying again",
+            Outcome::WrongLevel => "the part is already solved or still locked",
+            Outcome::Unknown => "unknown reply",
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
+/// Outcome of the page the website answers a submission with
+pub fn parse_outcome(page: &str) -> Outcome {
+    if page.contains("That's the right answer") {
+        Outcome::Right
+    } else if page.contains("your answer is too high") {
+        Outcome::TooHigh
+    } else if page.contains("your answer is too low") {
+        Outcome::TooLow
+    } else if page.contains("That's not the right answer") {
+        Outcome::Wrong
+    } else if page.contains("You gave an answer too recently") {
+        Outcome::RateLimited
+    } else if page.contains("You don't seem to be solving the right level") {
+        Outcome::WrongLevel
+    } else {
+        Outcome::Unknown
+    }
+}
+
+/// Answer submitted for a part, along with the reply it got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Submission {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+}
+
+impl Submission {
+    pub fn new(year: u16, day: u8, part: u8, answer: &str, outcome: Outcome) -> Submission {
+        Submission {
+            year,
+            day,
+            part,
+            answer: answer.to_owned(),
+            outcome,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        }
+    }
+}
+
+/// Reason not to submit an answer, known from previous submissions
+#[derive(Debug, PartialEq)]
+pub enum Refusal {
+    Solved(String),
+    KnownWrong,
+    TooHigh(i128),
+    TooLow(i128),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Solved(answer) => write!(f, "the part is already solved with {}", answer),
+            Refusal::KnownWrong => write!(f, "the answer was already submitted and is wrong"),
+            Refusal::TooHigh(bound) => write!(f, "the answer must be lower than {}", bound),
+            Refusal::TooLow(bound) => write!(f, "the answer must be higher than {}", bound),
+        }
+    }
+}
+
+/// Every answer submitted so far, stored as a JSON object per line
+#[derive(Debug)]
+pub struct SubmissionLog {
+    path: PathBuf,
+    pub submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    /// Reads the log, which is empty when the file does not exist
+    pub fn load(path: &Path) -> io::Result<SubmissionLog> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        let submissions = contents
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Submission>, _>>()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(SubmissionLog {
+            path: path.to_owned(),
+            submissions,
+        })
+    }
+
+    /// Adds a submission at the end of the log file
+    pub fn record(&mut self, submission: Submission) -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&submission)?)?;
+
+        self.submissions.push(submission);
+        Ok(())
+    }
+
+    /// Lowest and highest values the answer of a part can have, from the too low and too high
+    /// replies, both excluded
+    pub fn bounds(&self, year: u16, day: u8, part: u8) -> (Option<i128>, Option<i128>) {
+        let (mut low, mut high): (Option<i128>, Option<i128>) = (None, None);
+
+        for submission in self.of_part(year, day, part) {
+            let Ok(value) = submission.answer.parse::<i128>() else {
+                continue;
+            };
+            match submission.outcome {
+                Outcome::TooLow => low = Some(low.map_or(value, |l| l.max(value))),
+                Outcome::TooHigh => high = Some(high.map_or(value, |h| h.min(value))),
+                _ => {}
+            }
+        }
+
+        (low, high)
+    }
+
+    /// Checks whether an answer is worth submitting
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<(), Refusal> {
+        for submission in self.of_part(year, day, part) {
+            match submission.outcome {
+                Outcome::Right => return Err(Refusal::Solved(submission.answer.to_owned())),
+                Outcome::Wrong | Outcome::TooHigh | Outcome::TooLow
+                    if submission.answer == answer =>
+                {
+                    return Err(Refusal::KnownWrong)
+                }
+                _ => {}
+            }
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            match self.bounds(year, day, part) {
+                (_, Some(high)) if value >= high => return Err(Refusal::TooHigh(high)),
+                (Some(low), _) if value <= low => return Err(Refusal::TooLow(low)),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn of_part(&self, year: u16, day: u8, part: u8) -> impl Iterator<Item = &Submission> {
+        self.submissions
+            .iter()
+            .filter(move |s| (s.year, s.day, s.part) == (year, day, part))
+    }
+}
+
+/// Path of the submission log inside a data directory
+pub fn submissions_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SUBMISSIONS_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_log(outcomes: &[(&str, Outcome)]) -> SubmissionLog {
+        SubmissionLog {
+            path: PathBuf::new(),
+            submissions: outcomes
+                .iter()
+                .map(|(answer, outcome)| Submission::new(2022, 1, 1, answer, *outcome))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn it_should_parse_outcomes() {
+        let too_high = "<article><p>That's not the right answer; your answer is too high.</p>";
+        let recently = "<article><p>You gave an answer too recently; you have to wait</p>";
+
+        assert_eq!(parse_outcome(too_high), Outcome::TooHigh);
+        assert_eq!(parse_outcome(recently), Outcome::RateLimited);
+        assert_eq!(
+            parse_outcome("<p>That's the right answer! You are one gold star closer"),
+            Outcome::Right
+        );
+        assert_eq!(parse_outcome("<html></html>"), Outcome::Unknown);
+    }
+
+    #[test]
+    fn it_should_refuse_known_wrong_answers() {
+        let log = get_log(&[
+            ("100", Outcome::TooLow),
+            ("500", Outcome::TooHigh),
+            ("300", Outcome::Wrong),
+            ("200", Outcome::RateLimited),
+        ]);
+
+        assert_eq!(log.bounds(2022, 1, 1), (Some(100), Some(500)));
+        assert_eq!(log.check(2022, 1, 1, "300"), Err(Refusal::KnownWrong));
+        assert_eq!(log.check(2022, 1, 1, "600"), Err(Refusal::TooHigh(500)));
+        assert_eq!(log.check(2022, 1, 1, "50"), Err(Refusal::TooLow(100)));
+        assert_eq!(log.check(2022, 1, 1, "200"), Ok(()));
+        assert_eq!(log.check(2022, 1, 2, "300"), Ok(()));
+    }
+
+    #[test]
+    fn it_should_refuse_solved_parts() {
+        let log = get_log(&[("FRDSQRRCD", Outcome::Right)]);
+
+        assert_eq!(
+            log.check(2022, 1, 1, "HRFTQVWNN"),
+            Err(Refusal::Solved("FRDSQRRCD".to_owned()))
+        );
+    }
+}
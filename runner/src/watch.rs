@@ -0,0 +1,154 @@
+use crate::examples;
+use crate::run;
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::SystemTime;
+
+/// Files a day depends on: its module, its input and every file of its examples
+pub fn day_paths(crate_dir: &Path, data_dir: &Path, day: u8) -> Vec<PathBuf> {
+    vec![
+        crate_dir.join("src").join(format!("day{}.rs", day)),
+        run::input_path(data_dir, day),
+        examples::examples_dir(data_dir, day),
+    ]
+}
+
+/// Polls the modification times of files, and of the files inside directories
+#[derive(Debug)]
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    stamps: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Watcher {
+        let mut watcher = Watcher {
+            paths,
+            stamps: HashMap::new(),
+        };
+        watcher.stamps = watcher.scan();
+        watcher
+    }
+
+    /// Files created, modified or removed since the last call, sorted by path
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let stamps = self.scan();
+
+        let mut changed: Vec<PathBuf> = stamps
+            .iter()
+            .filter(|(path, stamp)| self.stamps.get(*path) != Some(stamp))
+            .map(|(path, _)| path.to_owned())
+            .chain(
+                self.stamps
+                    .keys()
+                    .filter(|path| !stamps.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        changed.sort();
+
+        self.stamps = stamps;
+        changed
+    }
+
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut stamps = HashMap::new();
+
+        for path in &self.paths {
+            let files = match fs::read_dir(path) {
+                Ok(entries) => entries.filter_map(|e| Some(e.ok()?.path())).collect(),
+                Err(_) => vec![path.to_owned()],
+            };
+            for file in files {
+                if let Ok(modified) = fs::metadata(&file).and_then(|m| m.modified()) {
+                    stamps.insert(file, modified);
+                }
+            }
+        }
+
+        stamps
+    }
+}
+
+fn cargo() -> Command {
+    Command::new(env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo")))
+}
+
+/// Runs the tests of a day in the crate at `crate_dir`, returning whether they passed
+pub fn run_tests(crate_dir: &Path, day: u8) -> io::Result<bool> {
+    let status = cargo()
+        .current_dir(crate_dir)
+        .args(["test", "--quiet", "--lib", "--"])
+        .arg(format!("day{}::", day))
+        .status()?;
+
+    Ok(status.success())
+}
+
+/// Builds and runs a part of a day with the runner binary, returning its answer or its error
+pub fn solve(
+    crate_dir: &Path,
+    year: u16,
+    day: u8,
+    part: u8,
+    data_dir: &Path,
+) -> io::Result<Result<String, String>> {
+    let output = cargo()
+        .current_dir(crate_dir)
+        .args(["run", "--quiet", "--package", env!("CARGO_PKG_NAME"), "--"])
+        .arg("--year")
+        .arg(year.to_string())
+        .arg("--day")
+        .arg(day.to_string())
+        .arg("--part")
+        .arg(part.to_string())
+        .arg("--data-dir")
+        .arg(data_dir)
+        .stdin(Stdio::null())
+        .output()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if output.status.success() {
+        Ok(Ok(stdout))
+    } else {
+        Ok(Err(String::from_utf8_lossy(&output.stderr).into_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+
+    #[test]
+    fn it_should_report_changed_files() {
+        let dir = env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        let examples = dir.join("examples");
+        fs::create_dir_all(&examples).unwrap();
+        fs::write(dir.join("day1.rs"), "").unwrap();
+        fs::write(examples.join("example.txt"), "").unwrap();
+        let mut watcher = Watcher::new(vec![dir.join("day1.rs"), examples.to_owned()]);
+
+        let unchanged = watcher.changed();
+        File::options()
+            .write(true)
+            .open(dir.join("day1.rs"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        fs::write(examples.join("larger.txt"), "").unwrap();
+        let changed = watcher.changed();
+
+        assert!(unchanged.is_empty());
+        assert_eq!(changed, [dir.join("day1.rs"), examples.join("larger.txt")]);
+        assert!(watcher.changed().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,36 @@
+use runner::answers::Verdict;
+use runner::examples;
+
+/// Solves every example of every registered day and checks the recorded answers
+#[test]
+fn it_should_solve_every_example() {
+    let mut failures: Vec<String> = vec![];
+    let mut checked = 0;
+
+    for event in runner::events() {
+        for example in examples::discover(&event.data_dir).unwrap() {
+            let label = format!("{} day {} {}", event.year, example.day, example.name);
+            let Some(mut solution) = event.get_solution(example.day) else {
+                failures.push(format!("{}: there is no solution for the day", label));
+                continue;
+            };
+
+            for (run, verdict) in examples::check(&example, solution.as_mut()).unwrap() {
+                checked += 1;
+                match verdict {
+                    Verdict::Pass | Verdict::Missing => {}
+                    Verdict::Fail { expected, actual } => failures.push(format!(
+                        "{} part {}: expected {:?}, got {:?}",
+                        label, run.part, expected, actual
+                    )),
+                    Verdict::Error(err) => {
+                        failures.push(format!("{} part {}: {}", label, run.part, err))
+                    }
+                }
+            }
+        }
+    }
+
+    assert!(checked > 0, "no examples found");
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}